@@ -7,7 +7,7 @@ fn main() {
     let mut args: Vec<String> = env::args().collect();
     args.remove(0);
 
-    if let None = args.first() {
+    if args.is_empty() {
         println!("You need to pass the input as an argument.");
         return;
     }
@@ -9,8 +9,10 @@
 //! - new formats are easier to add
 //! - combinations of date and time formats are declarative
 //!
-//! Right now, this crate only exposes one function: `parse`.
-//! This function should never panic, but since this library
+//! The main entry point is the `parse` function, while
+//! `parse_range` can be used to get the full span of inputs
+//! such as "next week".
+//! These functions should never panic, but since this library
 //! is still under development, if you find anything wrong
 //! or if it ever panics, please write an issue on the
 //! [`github repo`].
@@ -28,8 +30,13 @@
 //! [`github repo`]: https://github.com/andreadev-it/langtime
 
 use chrono::prelude::*;
+use nom::IResult;
 use nom::branch::alt;
-use parsers::combined::{full_datetime, dates, times};
+use nom::sequence::preceded;
+use parsers::combined::{full_datetime, dates, times, ranges};
+use parsers::iso::{iso_duration, iso_interval, iso_recurrence};
+use parsers::locale::preposition;
+use parsers::rfc::internet_dates;
 use parsers::relative::{
     relative_time_past,
     relative_time_future,
@@ -57,17 +64,9 @@ pub fn parse(input: &str) -> Result<DateTime<Local>, NotParsable> {
 pub fn parse_with_config(input: &str, config: &ParseConfig) -> Result<DateTime<Local>, NotParsable> {
     let input = input.trim().to_lowercase();
 
-    let mut alt_parse = alt((
-        times,
-        full_datetime(config),
-        dates(config),
-        relative_time_past,
-        relative_time_future,
-    ));
-
-    match alt_parse(&input) {
+    match datetime(config)(&input) {
         Ok((tail, dt)) => {
-            if tail != "" && config.full_string_match == true {
+            if !tail.is_empty() && config.full_string_match {
                 return Err(NotParsable)
             }
             Ok(dt)
@@ -76,6 +75,103 @@ pub fn parse_with_config(input: &str, config: &ParseConfig) -> Result<DateTime<L
     }
 }
 
+/// This function will take a string as an input
+/// and try to parse it into a range of Datetimes
+/// with the local timezone, returned as a
+/// `(start, end)` tuple. Inputs describing a period,
+/// like "next week", will return its first and last
+/// second, while any other input will return the
+/// same datetime for both the start and the end.
+pub fn parse_range(input: &str) -> Result<(DateTime<Local>, DateTime<Local>), NotParsable> {
+    let config = ParseConfig::default();
+
+    parse_range_with_config(input, &config)
+}
+
+/// Same as `parse_range`, but it takes a configuration
+/// that works in the same way as in `parse_with_config`.
+pub fn parse_range_with_config(input: &str, config: &ParseConfig) -> Result<(DateTime<Local>, DateTime<Local>), NotParsable> {
+    let input = input.trim().to_lowercase();
+
+    let range_res = ranges(config)(&input);
+    let single_res = datetime(config)(&input);
+
+    // Prefer whichever parser understood more of the input
+    let (tail, range) = match (range_res, single_res) {
+        (Ok((r_tail, range)), Ok((s_tail, _))) if r_tail.len() <= s_tail.len() => (r_tail, range),
+        (Ok((r_tail, range)), Err(_)) => (r_tail, range),
+        (_, Ok((s_tail, dt))) => (s_tail, (dt, dt)),
+        (Err(_), Err(_)) => return Err(NotParsable)
+    };
+
+    if !tail.is_empty() && config.full_string_match {
+        return Err(NotParsable)
+    }
+
+    Ok(range)
+}
+
+/// This function parses an ISO 8601 duration, such
+/// as "P3Y6M4DT12H30M5S", "PT15M" or "P2W". The whole
+/// input must be a duration.
+pub fn parse_duration(input: &str) -> Result<IsoDuration, NotParsable> {
+    let input = input.trim().to_lowercase();
+
+    match iso_duration(&input) {
+        Ok(("", duration)) => Ok(duration),
+        _ => Err(NotParsable)
+    }
+}
+
+/// This function parses an ISO 8601 interval, such as
+/// "2024-01-01/2024-02-01", "2024-01-01/P1M" or
+/// "P1M/2024-02-01", into a `(start, end)` tuple.
+/// The whole input must be an interval.
+pub fn parse_interval(input: &str) -> Result<(DateTime<Local>, DateTime<Local>), NotParsable> {
+    let input = input.trim().to_lowercase();
+
+    match iso_interval(&input) {
+        Ok(("", interval)) => Ok(interval),
+        _ => Err(NotParsable)
+    }
+}
+
+/// This function parses an ISO 8601 repeating interval,
+/// such as "R5/2024-01-01T09:00/P1W". The whole input
+/// must be a repeating interval.
+pub fn parse_recurrence(input: &str) -> Result<Recurrence, NotParsable> {
+    let input = input.trim().to_lowercase();
+
+    match iso_recurrence(&input) {
+        Ok(("", recurrence)) => Ok(recurrence),
+        _ => Err(NotParsable)
+    }
+}
+
+fn datetime(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, DateTime<Local>, ()> + '_ {
+    move |input: &str| {
+        alt((
+            preceded(preposition(config), datetime_parts(config)),
+            datetime_parts(config)
+        ))(input)
+    }
+}
+
+fn datetime_parts(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, DateTime<Local>, ()> + '_ {
+    move |input: &str| {
+        alt((
+            // Must come first, so that their time zone is not left behind
+            internet_dates(config),
+            // Must come before times, or "5pm tomorrow" would stop at "5pm"
+            full_datetime(config),
+            times(config),
+            dates(config),
+            relative_time_past,
+            relative_time_future,
+        ))(input)
+    }
+}
+
 /// A list of english dialects that will influence
 /// how the parser will convert the input string.
 /// For example, using mm-dd-yyyy instead of dd-mm-yyyy.
@@ -88,23 +184,282 @@ pub enum Dialect {
     UK
 }
 
+/// How to pick a date when the input leaves part of it
+/// out, like the month in "on the 15th" or the day in "9am".
+///
+#[derive(Eq, PartialEq, Debug)]
+pub enum Occurrence {
+    /// Always use the current one, even if it's already passed
+    Current,
+    /// The first occurrence from today onwards
+    Next,
+    /// The last occurrence up to today
+    Previous,
+    /// Whichever occurrence is closest to today
+    Nearest
+}
+
+/// What "next" means when it comes before a weekday,
+/// as in "next friday".
+///
+#[derive(Eq, PartialEq, Debug)]
+pub enum NextWeekday {
+    /// The first one after today, as in "the coming friday"
+    Coming,
+    /// The one in the following week
+    FollowingWeek
+}
+
+/// How weeks are numbered, as in "week 23".
+///
+#[derive(Eq, PartialEq, Debug)]
+pub enum WeekNumbering {
+    /// ISO 8601 weeks, starting on monday, where the first
+    /// week of the year is the one containing the 4th of january
+    Iso,
+    /// US weeks, starting on the configured `week_start`, where
+    /// the first week of the year is the one containing the 1st
+    /// of january
+    Us
+}
+
+/// How to complete years written with two digits,
+/// as in "12/06/24" or "jan '24".
+///
+#[derive(Eq, PartialEq, Debug)]
+pub enum TwoDigitYears {
+    /// Years below the pivot are in the 2000s,
+    /// the others in the 1900s
+    Pivot(u32),
+    /// Years are placed at most this many years
+    /// in the future, and otherwise in the past
+    Window(u32)
+}
+
+/// What to do when a date is preceded by a weekday
+/// that doesn't match it, as in "monday 13 september 2024".
+///
+#[derive(Eq, PartialEq, Debug)]
+pub enum WeekdayCheck {
+    /// Use the date and disregard the weekday
+    Ignore,
+    /// Refuse to parse the date
+    Fail
+}
+
+/// An ISO 8601 duration, like "P1Y2M" or "PT15M".
+/// Years and months are kept apart from the other
+/// parts, since their length depends on the date
+/// they are added to.
+///
+#[derive(Eq, PartialEq, Debug, Clone, Default)]
+pub struct IsoDuration {
+    /// Number of years
+    pub years: u32,
+    /// Number of months
+    pub months: u32,
+    /// Number of weeks
+    pub weeks: u32,
+    /// Number of days
+    pub days: u32,
+    /// Number of hours
+    pub hours: u32,
+    /// Number of minutes
+    pub minutes: u32,
+    /// Number of seconds
+    pub seconds: u32
+}
+
+impl IsoDuration {
+    /// Returns the datetime that comes this duration
+    /// after the given one. Adding a month to the 31st
+    /// of january gives the last day of february.
+    pub fn add_to(&self, dt: DateTime<Local>) -> Option<DateTime<Local>> {
+        self.shift(dt, 1)
+    }
+
+    /// Returns the datetime that comes this duration
+    /// before the given one.
+    pub fn sub_from(&self, dt: DateTime<Local>) -> Option<DateTime<Local>> {
+        self.shift(dt, -1)
+    }
+
+    fn shift(&self, dt: DateTime<Local>, sign: i64) -> Option<DateTime<Local>> {
+        let months = (self.years as i64 * 12 + self.months as i64) * sign;
+        let dt = utils::month_shift(dt, months).ok()?;
+
+        let exact = chrono::Duration::weeks(self.weeks as i64)
+            + chrono::Duration::days(self.days as i64)
+            + chrono::Duration::hours(self.hours as i64)
+            + chrono::Duration::minutes(self.minutes as i64)
+            + chrono::Duration::seconds(self.seconds as i64);
+
+        dt.checked_add_signed(exact * sign as i32)
+    }
+
+    fn times(&self, n: u32) -> Option<IsoDuration> {
+        Some(IsoDuration {
+            years: self.years.checked_mul(n)?,
+            months: self.months.checked_mul(n)?,
+            weeks: self.weeks.checked_mul(n)?,
+            days: self.days.checked_mul(n)?,
+            hours: self.hours.checked_mul(n)?,
+            minutes: self.minutes.checked_mul(n)?,
+            seconds: self.seconds.checked_mul(n)?
+        })
+    }
+}
+
+/// An ISO 8601 repeating interval, like
+/// "R5/2024-01-01T09:00/P1W".
+///
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct Recurrence {
+    /// How many times the interval repeats, or None
+    /// when it repeats forever ("R/...")
+    pub repetitions: Option<u32>,
+    /// The start of the first interval
+    pub start: DateTime<Local>,
+    /// The time between the start of an interval
+    /// and the next one
+    pub period: IsoDuration
+}
+
+impl Recurrence {
+    /// Returns the start of the nth interval, counting
+    /// from zero, or None if the recurrence has ended.
+    pub fn nth(&self, n: u32) -> Option<DateTime<Local>> {
+        if self.repetitions.is_some_and(|reps| n >= reps) {
+            return None;
+        }
+
+        // Always counting from the start keeps "P1M" on the same day
+        self.period.times(n)?.add_to(self.start)
+    }
+}
+
+/// The time of the day a named period refers to, as
+/// in "noon" or "this evening".
+///
+#[derive(Eq, PartialEq, Debug)]
+pub enum DayPeriod {
+    /// A precise time of the day
+    At(NaiveTime),
+    /// A span of time in the day. Its start will be used
+    /// when a single datetime is needed
+    Between(NaiveTime, NaiveTime)
+}
+
 /// The configuration for the langtime parse function
 pub struct ParseConfig {
     /// The english dialect to use
-    dialect: Dialect,
+    pub dialect: Dialect,
     /// Whether to match the whole string or not
-    full_string_match: bool
+    pub full_string_match: bool,
+    /// The first day of the week, used by inputs
+    /// such as "next week" or "this saturday"
+    pub week_start: Weekday,
+    /// How to count weeks in inputs such as "week 23"
+    pub week_numbering: WeekNumbering,
+    /// The first and last day of the weekend, used by
+    /// inputs such as "this weekend" or "next weekday"
+    pub weekend: (Weekday, Weekday),
+    /// The time at which the business day ends, used
+    /// by inputs such as "eod" or "cob"
+    pub business_day_end: NaiveTime,
+    /// Which month to use for days given without one,
+    /// such as "on the 15th"
+    pub day_of_month: Occurrence,
+    /// Which year to use for dates, months
+    /// or quarters given without one, such as "12/06",
+    /// "5th jan", "in march" or "end of q3"
+    pub year_of_date: Occurrence,
+    /// Which day to use for times given without one,
+    /// such as "9am"
+    pub day_of_time: Occurrence,
+    /// Which day "next" refers to, in inputs
+    /// such as "next friday"
+    pub next_weekday: NextWeekday,
+    /// Whether a weekday on its own, like "saturday",
+    /// can refer to today
+    pub weekday_includes_today: bool,
+    /// How to complete years written with two digits,
+    /// like "24" in "12/06/24"
+    pub two_digit_years: TwoDigitYears,
+    /// What to do with a weekday that doesn't match the
+    /// date it comes with, as in "fri, 12 sep 2024"
+    pub weekday_check: WeekdayCheck,
+    /// The names of the periods of the day, like "noon"
+    /// or "evening", with the time they refer to.
+    /// Names must be lowercase
+    pub day_periods: Vec<(String, DayPeriod)>,
+    /// The hour at which a new day starts for inputs such
+    /// as "tomorrow" or "tonight", so that at 1am with a
+    /// value of 4, "tomorrow" is still later today
+    pub day_rollover_hour: u32,
+    /// The range of hours that are considered pm when
+    /// written without am or pm, as in "tomorrow at 3".
+    /// When None, they are always considered am
+    pub bare_hours_pm: Option<(u32, u32)>,
+    /// The words and symbols that can separate a date
+    /// from a time, like "at" in "friday at 5pm" or ","
+    /// in "5pm, friday". Words can also lead the input,
+    /// as in "on friday", but only "at", "around" and
+    /// "at about" can lead a bare hour. Must be lowercase
+    pub connectors: Vec<String>
 }
 
 impl Default for ParseConfig {
     fn default() -> Self {
         ParseConfig {
             dialect: Dialect::UK,
-            full_string_match: false
+            full_string_match: false,
+            week_start: Weekday::Mon,
+            week_numbering: WeekNumbering::Iso,
+            weekend: (Weekday::Sat, Weekday::Sun),
+            business_day_end: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+            day_of_month: Occurrence::Next,
+            year_of_date: Occurrence::Current,
+            day_of_time: Occurrence::Current,
+            next_weekday: NextWeekday::FollowingWeek,
+            weekday_includes_today: true,
+            two_digit_years: TwoDigitYears::Window(20),
+            weekday_check: WeekdayCheck::Ignore,
+            day_periods: default_day_periods(),
+            day_rollover_hour: 0,
+            bare_hours_pm: Some((1, 6)),
+            connectors: default_connectors()
         }
     }
 }
 
+fn default_day_periods() -> Vec<(String, DayPeriod)> {
+    let time = |hour| NaiveTime::from_hms_opt(hour, 0, 0).unwrap();
+    let end_of_day = NaiveTime::from_hms_opt(23, 59, 59).unwrap();
+
+    vec![
+        ("noon".to_string(), DayPeriod::At(time(12))),
+        ("midday".to_string(), DayPeriod::At(time(12))),
+        ("midnight".to_string(), DayPeriod::At(time(0))),
+        ("morning".to_string(), DayPeriod::Between(time(8), time(12))),
+        ("breakfast".to_string(), DayPeriod::Between(time(7), time(10))),
+        ("lunchtime".to_string(), DayPeriod::Between(time(12), time(14))),
+        ("lunch".to_string(), DayPeriod::Between(time(12), time(14))),
+        ("afternoon".to_string(), DayPeriod::Between(time(13), time(17))),
+        ("evening".to_string(), DayPeriod::Between(time(18), time(21))),
+        ("dinner".to_string(), DayPeriod::Between(time(18), time(21))),
+        ("night".to_string(), DayPeriod::Between(time(20), end_of_day)),
+        ("tonight".to_string(), DayPeriod::Between(time(20), end_of_day))
+    ]
+}
+
+fn default_connectors() -> Vec<String> {
+    ["at", "at about", "around", "on", "by", "of", "@", ",", "-"]
+        .iter()
+        .map(|c| c.to_string())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -117,9 +472,13 @@ mod tests {
             dt.month() == 12 &&
             dt.year() == 2024
         );
+
+        assert!(parse("2024-w53-1").is_err());
+        assert!(parse("2023-366").is_err());
     }
 
     #[test]
+    #[allow(clippy::zero_prefixed_literal)]
     fn test_iso_datetime() {
         let dt = parse("2024-06-05T07:02:24").unwrap();
         assert!(
@@ -147,7 +506,8 @@ mod tests {
     fn test_dialect_us() {
         let config = ParseConfig {
             dialect: Dialect::US,
-            full_string_match: false
+            full_string_match: false,
+            ..Default::default()
         };
 
         let dt = parse_with_config("12/06/2024", &config).unwrap();
@@ -160,6 +520,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::zero_prefixed_literal, clippy::field_reassign_with_default)]
     fn test_match_full_text() {
         let mut config = ParseConfig::default();
         config.full_string_match = true;
@@ -178,4 +539,950 @@ mod tests {
             dt.year() == 2024
         );
     }
+
+    #[test]
+    fn test_period_range() {
+        let now = Local::now();
+        let (start, end) = parse_range("next month").unwrap();
+
+        let expected_month = now.month() % 12 + 1;
+        assert!(
+            start.day() == 1 &&
+            start.month() == expected_month &&
+            start.hour() == 0 &&
+            end.month() == expected_month &&
+            (end + chrono::Duration::seconds(1)).day() == 1
+        );
+
+        let dt = parse("next month").unwrap();
+        assert_eq!(dt, start);
+    }
+
+    #[test]
+    fn test_period_week_start() {
+        let config = ParseConfig {
+            week_start: Weekday::Sun,
+            ..Default::default()
+        };
+
+        let (start, end) = parse_range_with_config("this week", &config).unwrap();
+        assert!(
+            start.weekday() == Weekday::Sun &&
+            end.weekday() == Weekday::Sat &&
+            start <= Local::now() &&
+            end >= Local::now()
+        );
+    }
+
+    #[test]
+    fn test_period_boundaries() {
+        let now = Local::now();
+
+        let dt = parse("end of the month").unwrap();
+        let next_day = dt + chrono::Duration::seconds(1);
+        assert!(
+            dt.month() == now.month() &&
+            next_day.day() == 1 &&
+            next_day.hour() == 0
+        );
+
+        let dt = parse("beginning of q2 2025").unwrap();
+        assert!(
+            dt.day() == 1 &&
+            dt.month() == 4 &&
+            dt.year() == 2025
+        );
+
+        // Two-digit years follow `two_digit_years`, other lengths are not years
+        let dt = parse("end of march 24").unwrap();
+        assert!(
+            dt.day() == 31 &&
+            dt.month() == 3 &&
+            dt.year() == 2024
+        );
+
+        let dt = parse("end of 24").unwrap();
+        assert!(
+            dt.day() == 31 &&
+            dt.month() == 12 &&
+            dt.year() == 2024
+        );
+
+        let dt = parse("second tuesday of march 24").unwrap();
+        assert!(
+            dt.day() == 12 &&
+            dt.month() == 3 &&
+            dt.year() == 2024
+        );
+
+        assert!(parse("end of march 123").map_or(true, |dt| dt.year() != 123));
+
+        let dt = parse("start of next week").unwrap();
+        assert!(
+            dt.weekday() == Weekday::Mon &&
+            dt > now &&
+            dt - now <= chrono::Duration::days(7)
+        );
+    }
+
+    #[test]
+    fn test_business_deadlines() {
+        let config = ParseConfig {
+            business_day_end: NaiveTime::from_hms_opt(18, 30, 0).unwrap(),
+            ..Default::default()
+        };
+
+        let tomorrow = Local::now() + chrono::Duration::days(1);
+        let dt = parse_with_config("cob tomorrow", &config).unwrap();
+        assert!(
+            dt.day() == tomorrow.day() &&
+            dt.hour() == 18 &&
+            dt.minute() == 30
+        );
+
+        let dt = parse("eow").unwrap();
+        assert!(
+            dt.weekday() == Weekday::Fri &&
+            dt.hour() == 17
+        );
+    }
+
+    #[test]
+    fn test_nth_weekday() {
+        let dt = parse("the last sunday in october 2025").unwrap();
+        assert!(
+            dt.day() == 26 &&
+            dt.month() == 10 &&
+            dt.year() == 2025
+        );
+
+        let dt = parse("second tuesday of march 2024").unwrap();
+        assert!(
+            dt.day() == 12 &&
+            dt.month() == 3 &&
+            dt.year() == 2024
+        );
+
+        let now = Local::now();
+        let dt = parse("last friday of the month").unwrap();
+        assert!(
+            dt.weekday() == Weekday::Fri &&
+            dt.month() == now.month() &&
+            (dt + chrono::Duration::days(7)).month() != now.month()
+        );
+
+        // February 2026 has only four fridays
+        assert!(parse("5th friday of february 2026").is_err());
+    }
+
+    #[test]
+    fn test_ordinal_day() {
+        let today = Local::now().date_naive();
+
+        let dt = parse("on the 1st").unwrap();
+        assert!(
+            dt.day() == 1 &&
+            dt.date_naive() >= today
+        );
+
+        let config = ParseConfig {
+            day_of_month: Occurrence::Previous,
+            ..Default::default()
+        };
+
+        let dt = parse_with_config("the 1st", &config).unwrap();
+        assert!(
+            dt.day() == 1 &&
+            dt.date_naive() <= today
+        );
+    }
+
+    #[test]
+    fn test_date_without_year() {
+        let year = Local::now().year();
+
+        let dt = parse("12/06").unwrap();
+        assert!(
+            dt.day() == 12 &&
+            dt.month() == 6 &&
+            dt.year() == year
+        );
+
+        let config = ParseConfig {
+            dialect: Dialect::US,
+            year_of_date: Occurrence::Next,
+            ..Default::default()
+        };
+
+        let dt = parse_with_config("12/06", &config).unwrap();
+        assert!(
+            dt.day() == 6 &&
+            dt.month() == 12 &&
+            dt.date_naive() >= Local::now().date_naive()
+        );
+
+        let dt = parse("06/2024").unwrap();
+        assert!(
+            dt.day() == 1 &&
+            dt.month() == 6 &&
+            dt.year() == 2024
+        );
+    }
+
+    #[test]
+    fn test_year_policy() {
+        let today = Local::now().date_naive();
+
+        let config = ParseConfig {
+            year_of_date: Occurrence::Next,
+            ..Default::default()
+        };
+
+        let dt = parse_with_config("5th jan", &config).unwrap();
+        assert!(
+            dt.day() == 5 &&
+            dt.month() == 1 &&
+            dt.date_naive() >= today
+        );
+
+        let config = ParseConfig {
+            year_of_date: Occurrence::Previous,
+            ..Default::default()
+        };
+
+        let dt = parse_with_config("in december", &config).unwrap();
+        assert!(
+            dt.day() == 1 &&
+            dt.month() == 12 &&
+            dt.date_naive() <= today
+        );
+
+        for quarter in ["q1", "q2", "q3", "q4"] {
+            let dt = parse_with_config(&format!("start of {}", quarter), &config).unwrap();
+            assert!(dt.date_naive() <= today);
+        }
+
+        let config = ParseConfig {
+            year_of_date: Occurrence::Next,
+            ..Default::default()
+        };
+
+        for quarter in ["q1", "q2", "q3", "q4"] {
+            let dt = parse_with_config(&format!("end of {}", quarter), &config).unwrap();
+            assert!(dt.date_naive() >= today);
+        }
+    }
+
+    #[test]
+    fn test_relative_months() {
+        let now = Local::now();
+
+        let (start, end) = parse_range("next march").unwrap();
+        assert!(
+            start.day() == 1 &&
+            start.month() == 3 &&
+            start > now &&
+            end.day() == 31 &&
+            end.month() == 3
+        );
+
+        let dt = parse("last december").unwrap();
+        assert!(
+            dt.month() == 12 &&
+            dt < now
+        );
+
+        let dt = parse("june 5 12:30").unwrap();
+        assert!(
+            dt.day() == 5 &&
+            dt.month() == 6 &&
+            dt.hour() == 12 &&
+            dt.minute() == 30
+        );
+
+        let dt = parse("jan 24").unwrap();
+        assert!(
+            dt.day() == 24 &&
+            dt.month() == 1
+        );
+
+        let dt = parse("june 5th next year").unwrap();
+        assert!(
+            dt.day() == 5 &&
+            dt.month() == 6 &&
+            dt.year() == now.year() + 1
+        );
+
+        // Spelled months are read in both orders, whatever the dialect
+        let config = ParseConfig {
+            dialect: Dialect::US,
+            ..Default::default()
+        };
+        let dt = parse_with_config("5th june next year", &config).unwrap();
+        assert!(
+            dt.day() == 5 &&
+            dt.month() == 6 &&
+            dt.year() == now.year() + 1
+        );
+    }
+
+    #[test]
+    fn test_next_weekday() {
+        let today = Local::now().date_naive();
+
+        let config = ParseConfig {
+            next_weekday: NextWeekday::Coming,
+            ..Default::default()
+        };
+
+        let dt = parse_with_config("next friday", &config).unwrap();
+        let days = (dt.date_naive() - today).num_days();
+        assert!(
+            dt.weekday() == Weekday::Fri &&
+            (1..=7).contains(&days)
+        );
+
+        let (start, end) = parse_range("next week").unwrap();
+        let dt = parse("next friday").unwrap();
+        assert!(
+            dt.weekday() == Weekday::Fri &&
+            dt >= start &&
+            dt <= end
+        );
+    }
+
+    #[test]
+    fn test_bare_weekday() {
+        let today = Local::now().date_naive();
+
+        let dt = parse("saturday").unwrap();
+        let days = (dt.date_naive() - today).num_days();
+        assert!(
+            dt.weekday() == Weekday::Sat &&
+            (0..=6).contains(&days)
+        );
+
+        let config = ParseConfig {
+            weekday_includes_today: false,
+            ..Default::default()
+        };
+
+        let dt = parse_with_config(&today.weekday().to_string(), &config).unwrap();
+        assert_eq!((dt.date_naive() - today).num_days(), 7);
+    }
+
+    #[test]
+    fn test_week_weekdays() {
+        let (start, end) = parse_range("next week").unwrap();
+
+        let dt = parse("monday next week").unwrap();
+        assert!(
+            dt.weekday() == Weekday::Mon &&
+            dt >= start &&
+            dt <= end
+        );
+
+        let (start, end) = parse_range("this week").unwrap();
+        let dt = parse("wednesday of this week").unwrap();
+        assert!(
+            dt.weekday() == Weekday::Wed &&
+            dt >= start &&
+            dt <= end
+        );
+
+        let today = Local::now().date_naive();
+        let dt = parse("friday after next").unwrap();
+        let days = (dt.date_naive() - today).num_days();
+        assert!(
+            dt.weekday() == Weekday::Fri &&
+            (8..=14).contains(&days)
+        );
+
+        assert_eq!(
+            parse("tuesday week").unwrap().date_naive(),
+            parse("a week from tuesday").unwrap().date_naive()
+        );
+    }
+
+    #[test]
+    fn test_week_start() {
+        let config = ParseConfig {
+            week_start: Weekday::Sun,
+            ..Default::default()
+        };
+
+        let (start, end) = parse_range_with_config("this week", &config).unwrap();
+        let dt = parse_with_config("this saturday", &config).unwrap();
+        assert!(
+            dt.weekday() == Weekday::Sat &&
+            dt >= start &&
+            dt <= end
+        );
+
+        let (start, end) = parse_range_with_config("week 1 of 2021", &config).unwrap();
+        assert!(
+            start.day() == 4 &&
+            start.month() == 1 &&
+            end.day() == 10
+        );
+
+        let config = ParseConfig {
+            week_numbering: WeekNumbering::Us,
+            ..config
+        };
+
+        let dt = parse_with_config("week 1 2021", &config).unwrap();
+        assert!(
+            dt.day() == 27 &&
+            dt.month() == 12 &&
+            dt.year() == 2020
+        );
+
+        let config = ParseConfig {
+            full_string_match: true,
+            ..config
+        };
+        assert!(parse_range_with_config("week 4000000000", &config).is_err());
+    }
+
+    #[test]
+    fn test_weekends() {
+        let today = Local::now().date_naive();
+
+        let (start, end) = parse_range("this weekend").unwrap();
+        assert!(
+            start.weekday() == Weekday::Sat &&
+            end.weekday() == Weekday::Sun &&
+            end.date_naive() >= today &&
+            (start.date_naive() - today).num_days() < 7
+        );
+
+        let config = ParseConfig {
+            weekend: (Weekday::Fri, Weekday::Sat),
+            ..Default::default()
+        };
+
+        let (start, end) = parse_range_with_config("last weekend", &config).unwrap();
+        assert!(
+            start.weekday() == Weekday::Fri &&
+            end.weekday() == Weekday::Sat &&
+            end.date_naive() < today
+        );
+
+        let dt = parse_with_config("the next weekday", &config).unwrap();
+        assert!(
+            dt.weekday() != Weekday::Fri &&
+            dt.weekday() != Weekday::Sat &&
+            dt.date_naive() > today
+        );
+        let (start, end) = parse_range_with_config("on weekdays", &config).unwrap();
+        assert!(
+            start.date_naive() >= today &&
+            start.weekday() != Weekday::Fri &&
+            start.weekday() != Weekday::Sat &&
+            end.weekday() == Weekday::Thu &&
+            (end - start).num_days() < 5
+        );
+
+        // A weekend covering the whole week leaves no working days
+        let config = ParseConfig {
+            weekend: (Weekday::Sun, Weekday::Sat),
+            full_string_match: true,
+            ..Default::default()
+        };
+        assert!(parse_with_config("next weekday", &config).is_err());
+        assert!(parse_range_with_config("on weekdays", &config).is_err());
+    }
+
+    #[test]
+    fn test_day_periods() {
+        let today = Local::now().date_naive();
+
+        let dt = parse("noon").unwrap();
+        assert!(
+            dt.date_naive() == today &&
+            dt.hour() == 12 &&
+            dt.minute() == 0
+        );
+
+        let dt = parse("tomorrow morning").unwrap();
+        assert!(
+            (dt.date_naive() - today).num_days() == 1 &&
+            dt.hour() == 8
+        );
+
+        let mut config = ParseConfig::default();
+        config.day_periods.push((
+            "teatime".to_string(),
+            DayPeriod::At(NaiveTime::from_hms_opt(16, 30, 0).unwrap())
+        ));
+
+        let dt = parse_with_config("friday at teatime", &config).unwrap();
+        assert!(
+            dt.weekday() == Weekday::Fri &&
+            dt.hour() == 16 &&
+            dt.minute() == 30
+        );
+
+        let (start, end) = parse_range("this evening").unwrap();
+        assert!(
+            start.date_naive() == today &&
+            start.hour() == 18 &&
+            end.hour() == 21
+        );
+    }
+
+    #[test]
+    fn test_meridiem_context() {
+        let dt = parse("5 in the evening").unwrap();
+        assert!(dt.hour() == 17);
+
+        let dt = parse("7 in the morning").unwrap();
+        assert!(dt.hour() == 7);
+
+        for (input, hour) in [("1 in the morning", 1), ("2 in the morning", 2), ("3 in the morning", 3), ("this morning at 1", 1)] {
+            let dt = parse(input).unwrap();
+            assert!(dt.hour() == hour);
+        }
+
+        let dt = parse("3 at night").unwrap();
+        assert!(dt.hour() == 3);
+
+        let dt = parse("tonight at 8:30").unwrap();
+        assert!(
+            dt.hour() == 20 &&
+            dt.minute() == 30
+        );
+
+        let dt = parse("dinner at 7").unwrap();
+        assert!(dt.hour() == 19);
+
+        let today = Local::now().date_naive();
+        let dt = parse("tomorrow at 3").unwrap();
+        assert!(
+            (dt.date_naive() - today).num_days() == 1 &&
+            dt.hour() == 15
+        );
+
+        let config = ParseConfig {
+            bare_hours_pm: None,
+            ..Default::default()
+        };
+
+        let dt = parse_with_config("tomorrow at 3", &config).unwrap();
+        assert!(dt.hour() == 3);
+
+        // Only some connectors can lead a bare hour
+        let dt = parse("around 3").unwrap();
+        assert!(dt.hour() == 15);
+
+        for input in ["on 3", "of 3", "tomorrow on 3", "tomorrow by 3"] {
+            assert!(parse(input).map_or(true, |dt| dt.hour() != 15));
+        }
+    }
+
+    #[test]
+    fn test_spoken_clock() {
+        let times = [
+            ("twenty past six", 6, 20),
+            ("ten to five", 4, 50),
+            ("half six", 6, 30),
+            ("quarter past 9", 9, 15),
+            ("a quarter to one", 0, 45),
+            ("25 minutes past eleven", 11, 25),
+            ("twenty-five to 3 pm", 14, 35),
+            ("quarter to nine in the evening", 20, 45)
+        ];
+
+        for (input, hour, minute) in times {
+            let dt = parse(input).unwrap();
+            assert_eq!((dt.hour(), dt.minute()), (hour, minute), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_time_separators() {
+        let times = [
+            ("5.30pm", 17, 30),
+            ("17.45", 17, 45),
+            ("17h30", 17, 30),
+            ("17h", 17, 0),
+            ("1730 hrs", 17, 30),
+            ("0900 hours", 9, 0),
+            ("0715", 7, 15)
+        ];
+
+        for (input, hour, minute) in times {
+            let dt = parse(input).unwrap();
+            assert_eq!((dt.hour(), dt.minute()), (hour, minute), "{}", input);
+        }
+
+        let dt = parse("0900z").unwrap();
+        assert_eq!(dt.with_timezone(&Utc).hour(), 9);
+
+        let dt = parse("tomorrow at 24:00").unwrap();
+        let after_tomorrow = Local::now().date_naive() + chrono::Duration::days(2);
+        assert!(
+            dt.date_naive() == after_tomorrow &&
+            dt.hour() == 0 &&
+            dt.second() == 0
+        );
+
+        // Four digits without a suffix are a year, not a time
+        let dt = parse("1730").unwrap();
+        assert!(
+            dt.year() == 1730 &&
+            dt.hour() == 0
+        );
+    }
+
+    #[test]
+    fn test_time_policy() {
+        let now = Local::now();
+
+        let config = ParseConfig {
+            day_of_time: Occurrence::Next,
+            ..Default::default()
+        };
+
+        for input in ["9am", "09:00", "nine o'clock", "quarter past nine"] {
+            let dt = parse_with_config(input, &config).unwrap();
+            assert!(
+                dt >= now.round_subsecs(0) &&
+                dt - now < chrono::Duration::days(1)
+            );
+        }
+
+        for input in ["5 in the morning", "9 in the evening", "noon", "midnight", "this morning", "tonight"] {
+            let dt = parse_with_config(input, &config).unwrap();
+            assert!(
+                dt >= now.round_subsecs(0) &&
+                dt - now <= chrono::Duration::days(1)
+            );
+        }
+
+        // Periods of an earlier day are not moved
+        let dt = parse_with_config("last night", &config).unwrap();
+        assert!(dt < now);
+
+        // Midnight is the coming one, under the default policy too
+        let tomorrow = now.date_naive() + chrono::Duration::days(1);
+        for config in [ParseConfig::default(), config] {
+            let dt = parse_with_config("midnight", &config).unwrap();
+            assert!(
+                dt > now &&
+                dt.date_naive() == tomorrow &&
+                dt.hour() == 0 &&
+                dt.minute() == 0
+            );
+        }
+
+        let config = ParseConfig {
+            day_of_time: Occurrence::Previous,
+            ..Default::default()
+        };
+
+        let dt = parse_with_config("11:59 pm", &config).unwrap();
+        assert!(dt <= now);
+
+        let dt = parse_with_config("noon", &config).unwrap();
+        assert!(dt <= now);
+
+        // Times with a date are not affected
+        let dt = parse_with_config("tomorrow at 11:59 pm", &config).unwrap();
+        assert!(dt > now);
+    }
+
+    #[test]
+    fn test_day_rollover() {
+        let now = Local::now();
+        let today = now.date_naive();
+
+        // With a rollover at the current hour or later, the day hasn't started yet.
+        // A rollover at 24 isn't allowed, so this can't be checked after 23:00
+        if now.hour() < 23 {
+            let config = ParseConfig {
+                day_rollover_hour: now.hour() + 1,
+                ..Default::default()
+            };
+
+            let dt = parse_with_config("tomorrow", &config).unwrap();
+            assert_eq!(dt.date_naive(), today);
+
+            let dt = parse_with_config("today", &config).unwrap();
+            assert!(
+                (today - dt.date_naive()).num_days() == 1 &&
+                (dt.time() - now.time()).num_seconds().abs() <= 1
+            );
+
+            let dt = parse_with_config("tonight", &config).unwrap();
+            assert_eq!((today - dt.date_naive()).num_days(), 1);
+
+            let dt = parse_with_config("tonight at 2", &config).unwrap();
+            assert!(
+                dt.date_naive() == today &&
+                dt.hour() == 2
+            );
+        }
+
+        let dt = parse("tonight at 1").unwrap();
+        assert!(
+            (dt.date_naive() - today).num_days() == 1 &&
+            dt.hour() == 1
+        );
+
+        // An evening hour is not moved to the next day
+        let dt = parse("5 in the evening").unwrap();
+        assert!(
+            dt.date_naive() == today &&
+            dt.hour() == 17
+        );
+
+        let dt = parse("today").unwrap();
+        assert!((dt - now).num_seconds().abs() <= 1);
+
+        let config = ParseConfig {
+            day_rollover_hour: 24,
+            full_string_match: true,
+            ..Default::default()
+        };
+        assert!(parse_with_config("tomorrow", &config).is_err());
+    }
+
+    #[test]
+    fn test_time_before_date() {
+        let today = Local::now().date_naive();
+
+        let dt = parse("5pm tomorrow").unwrap();
+        assert!(
+            (dt.date_naive() - today).num_days() == 1 &&
+            dt.hour() == 17
+        );
+
+        let dt = parse("at 9 on friday").unwrap();
+        assert!(
+            dt.weekday() == Weekday::Fri &&
+            dt.hour() == 9
+        );
+
+        let dt = parse("noon next monday").unwrap();
+        assert!(
+            dt.weekday() == Weekday::Mon &&
+            dt.date_naive() > today &&
+            dt.hour() == 12
+        );
+
+        let dt = parse("17:30, 12/06/2024").unwrap();
+        assert!(
+            dt.day() == 12 &&
+            dt.month() == 6 &&
+            dt.hour() == 17 &&
+            dt.minute() == 30
+        );
+    }
+
+    #[test]
+    fn test_connectors() {
+        let tomorrow = Local::now().date_naive() + chrono::Duration::days(1);
+
+        for input in ["tomorrow @ 5pm", "tomorrow, 17:00", "tomorrow - 17:00", "tomorrow by 5pm", "tomorrow at about 5", "at 5pm tomorrow"] {
+            let dt = parse(input).unwrap();
+            assert!(
+                dt.date_naive() == tomorrow &&
+                dt.hour() == 17
+            );
+        }
+
+        let dt = parse("on friday").unwrap();
+        assert!(dt.weekday() == Weekday::Fri);
+
+        let config = ParseConfig {
+            connectors: vec!["at".to_string()],
+            full_string_match: true,
+            ..Default::default()
+        };
+        assert!(parse_with_config("tomorrow at 5pm", &config).is_ok());
+        assert!(parse_with_config("tomorrow @ 5pm", &config).is_err());
+    }
+
+    #[test]
+    fn test_weekday_dates() {
+        let dt = parse("fri, 13 sep 2024").unwrap();
+        assert!(
+            dt.day() == 13 &&
+            dt.month() == 9 &&
+            dt.year() == 2024
+        );
+
+        let dt = parse("friday, september 13th 2024 at 5pm").unwrap();
+        assert!(
+            dt.day() == 13 &&
+            dt.hour() == 17
+        );
+
+        let dt = parse("monday the 15th").unwrap();
+        assert!(
+            dt.day() == 15 &&
+            dt.weekday() == Weekday::Mon &&
+            dt.date_naive() >= Local::now().date_naive().with_day(1).unwrap()
+        );
+
+        let dt = parse("tuesday 15/06").unwrap();
+        assert!(
+            dt.day() == 15 &&
+            dt.month() == 6 &&
+            dt.weekday() == Weekday::Tue
+        );
+
+        let dt = parse("thu 13/09/2024").unwrap();
+        assert!(dt.day() == 13);
+
+        let config = ParseConfig {
+            weekday_check: WeekdayCheck::Fail,
+            ..Default::default()
+        };
+        assert!(parse_with_config("friday 13 september 2024", &config).is_ok());
+        assert!(parse_with_config("thursday 13 september 2024", &config).is_err());
+        assert!(parse_with_config("sun, 06 nov 1994 08:49:37 gmt", &config).is_ok());
+        assert!(parse_with_config("mon, 06 nov 1994 08:49:37 gmt", &config).is_err());
+    }
+
+    #[test]
+    fn test_date_separators() {
+        for input in ["12.06.2024", "12-06-2024", "12 06 2024", "2024/06/12", "2024.6.12", "20240612"] {
+            let dt = parse(input).unwrap();
+            assert!(
+                dt.day() == 12 &&
+                dt.month() == 6 &&
+                dt.year() == 2024
+            );
+        }
+
+        let config = ParseConfig {
+            dialect: Dialect::US,
+            ..Default::default()
+        };
+        let dt = parse_with_config("06.12.2024", &config).unwrap();
+        assert!(
+            dt.day() == 12 &&
+            dt.month() == 6
+        );
+
+        let dt = parse("06.2024").unwrap();
+        assert!(
+            dt.day() == 1 &&
+            dt.month() == 6 &&
+            dt.year() == 2024
+        );
+    }
+
+    #[test]
+    fn test_two_digit_years() {
+        let dt = parse("12/06/24").unwrap();
+        assert!(
+            dt.day() == 12 &&
+            dt.month() == 6 &&
+            dt.year() == 2024
+        );
+
+        let dt = parse("jan '24").unwrap();
+        assert!(
+            dt.month() == 1 &&
+            dt.year() == 2024
+        );
+
+        let dt = parse("5 jan 99").unwrap();
+        assert!(dt.year() == 1999);
+
+        let config = ParseConfig {
+            two_digit_years: TwoDigitYears::Pivot(99),
+            ..Default::default()
+        };
+        let dt = parse_with_config("5 jan 99", &config).unwrap();
+        assert!(dt.year() == 1999);
+
+        let dt = parse_with_config("5 jan 98", &config).unwrap();
+        assert!(dt.year() == 2098);
+
+        // Years with three digits are not accepted
+        assert!(parse("12/06/024").is_err());
+    }
+
+    #[test]
+    fn test_iso_durations() {
+        let duration = parse_duration("P3Y6M4DT12H30M5S").unwrap();
+        assert!(duration == IsoDuration {
+            years: 3,
+            months: 6,
+            days: 4,
+            hours: 12,
+            minutes: 30,
+            seconds: 5,
+            ..Default::default()
+        });
+
+        assert!(parse_duration("PT15M").unwrap().minutes == 15);
+        assert!(parse_duration("P2W").unwrap().weeks == 2);
+        assert!(parse_duration("P").is_err());
+        assert!(parse_duration("P1DT").is_err());
+
+        let (start, end) = parse_interval("2024-01-01/2024-02-01").unwrap();
+        assert!(start.month() == 1 && end.month() == 2);
+
+        let (start, end) = parse_interval("2024-01-31/P1M").unwrap();
+        assert!(
+            start.day() == 31 &&
+            end.month() == 2 &&
+            end.day() == 29
+        );
+
+        let (start, _) = parse_interval("PT1H/2024-01-01T09:00").unwrap();
+        assert!(start.hour() == 8);
+
+        // Month counts too large for a date are rejected
+        assert!(parse_interval("P2147483648M/2024-02-01").is_err());
+        assert!(parse_interval("2024-01-01/P2147483647M").is_err());
+
+        // 24:00 is the start of the next day
+        let (start, end) = parse_interval("2024-06-05T00:00/2024-06-05T24:00").unwrap();
+        assert!(end - start == chrono::Duration::days(1));
+
+        let recurrence = parse_recurrence("R5/2024-01-01T09:00/P1W").unwrap();
+        assert!(recurrence.repetitions == Some(5));
+        let third = recurrence.nth(2).unwrap();
+        assert!(
+            third.day() == 15 &&
+            third.hour() == 9
+        );
+        assert!(recurrence.nth(5).is_none());
+
+        let recurrence = parse_recurrence("R/2024-01-01/2024-01-03").unwrap();
+        assert!(
+            recurrence.repetitions.is_none() &&
+            recurrence.nth(100).is_some()
+        );
+    }
+
+    #[test]
+    fn test_internet_dates() {
+        let inputs = [
+            "Sun, 06 Nov 1994 08:49:37 GMT",
+            "Sunday, 06-Nov-94 08:49:37 GMT",
+            "Sun Nov  6 08:49:37 1994",
+            "Sun, 06 Nov 1994 03:49:37 EST",
+            "1994-11-06T09:49:37+01:00"
+        ];
+
+        for input in inputs {
+            let dt = parse(input).unwrap().with_timezone(&Utc);
+            assert!(
+                dt.year() == 1994 &&
+                dt.month() == 11 &&
+                dt.day() == 6 &&
+                dt.hour() == 8 &&
+                dt.minute() == 49 &&
+                dt.second() == 37
+            );
+        }
+    }
 }
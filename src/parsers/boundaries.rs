@@ -0,0 +1,133 @@
+use chrono::prelude::*;
+use nom::{Parser, IResult};
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::{one_of, space1};
+use nom::combinator::opt;
+use nom::sequence::{preceded, terminated, tuple};
+use crate::ParseConfig;
+
+use crate::parsers::generic::{month_name, word_end};
+use crate::parsers::locale::{week_numbers, year_digits};
+use crate::utils::{extract_datetime, month_string_to_int, period_bounds, resolve_month};
+
+pub type Bounds = (DateTime<Local>, DateTime<Local>);
+
+pub fn period_boundaries(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, DateTime<Local>, ()> + '_ {
+    move |input: &str| {
+        let (tail, (edge, _, _)) = tuple((
+            alt((
+                tag("start"),
+                tag("beginning"),
+                tag("end")
+            )),
+            tag(" of "),
+            opt(tag("the "))
+        )).parse(input)?;
+
+        let (tail, (start, end)) = alt((
+            week_numbers(config),
+            current_period(config),
+            quarter(config),
+            month_of_year(config),
+            calendar_year(config)
+        )).parse(tail)?;
+
+        match edge {
+            "end" => Ok((tail, end)),
+            _ => Ok((tail, start))
+        }
+    }
+}
+
+pub fn current_period(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, Bounds, ()> + '_ {
+    move |input: &str| {
+        let (tail, (rel, period)) = tuple((
+            opt(terminated(
+                alt((
+                    tag("this"),
+                    tag("next"),
+                    tag("last")
+                )),
+                space1
+            )),
+            terminated(
+                alt((
+                    tag("day"),
+                    tag("week"),
+                    tag("month"),
+                    tag("quarter"),
+                    tag("year")
+                )),
+                word_end
+            )
+        )).parse(input)?;
+
+        let offset = match rel {
+            Some("next") => 1,
+            Some("last") => -1,
+            _ => 0
+        };
+
+        let now = Local::now().round_subsecs(0);
+        let bounds = period_bounds(now, period, offset, config.week_start)?;
+
+        Ok((tail, bounds))
+    }
+}
+
+pub fn quarter(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, Bounds, ()> + '_ {
+    move |input: &str| {
+        let (tail, (quarter, year_opt)) = tuple((
+            preceded(tag("q"), one_of("1234")),
+            opt(preceded(space1, year_digits(config)))
+        )).parse(input)?;
+
+        // Safe, since the digit has already been checked by the parser
+        let month = (quarter.to_digit(10).unwrap() - 1) * 3 + 1;
+
+        let first = match year_opt {
+            Some(year) => extract_datetime(Local.with_ymd_and_hms(year, month, 1, 0, 0, 0))?,
+            None => {
+                // Starting from the current quarter makes it both the next and the previous one
+                let (current, _) = period_bounds(Local::now(), "quarter", 0, config.week_start)?;
+                resolve_month(current, month, &config.year_of_date)?
+            }
+        };
+        let bounds = period_bounds(first, "quarter", 0, config.week_start)?;
+
+        Ok((tail, bounds))
+    }
+}
+
+pub fn month_of_year(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, Bounds, ()> + '_ {
+    move |input: &str| {
+        let (tail, (m_str, _, year_opt)) = tuple((
+            month_name,
+            word_end,
+            opt(preceded(space1, year_digits(config)))
+        )).parse(input)?;
+
+        let month = month_string_to_int(m_str)
+                    .map_err(|_| nom::Err::<()>::Error(()))?;
+
+        let first = match year_opt {
+            Some(year) => extract_datetime(Local.with_ymd_and_hms(year, month, 1, 0, 0, 0))?,
+            None => resolve_month(Local::now(), month, &config.year_of_date)?
+        };
+        let bounds = period_bounds(first, "month", 0, config.week_start)?;
+
+        Ok((tail, bounds))
+    }
+}
+
+pub fn calendar_year(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, Bounds, ()> + '_ {
+    move |input: &str| {
+        let (tail, year) = terminated(year_digits(config), word_end).parse(input)?;
+
+        let first = extract_datetime(Local.with_ymd_and_hms(year, 1, 1, 0, 0, 0))?;
+        let bounds = period_bounds(first, "year", 0, Weekday::Mon)?;
+
+        Ok((tail, bounds))
+    }
+}
@@ -1,40 +1,99 @@
 use chrono::prelude::*;
 use nom::branch::alt;
-use nom::IResult;
-use crate::{Dialect, ParseConfig};
+use nom::bytes::complete::tag;
+use nom::character::complete::space1;
+use nom::combinator::{map, opt};
+use nom::sequence::{terminated, tuple};
+use nom::{IResult, Parser};
+use crate::{ParseConfig, WeekdayCheck};
 
-use crate::parsers::iso::parse_iso_date;
-use crate::parsers::locale::{named_dates, named_months, parse_dmy, parse_mdy, parse_my, spelled_dates_uk, spelled_dates_us};
+use crate::parsers::generic::{weekday, word_end};
+
+use crate::parsers::boundaries::period_boundaries;
+use crate::parsers::iso::{parse_iso_dates, parse_iso_reduced_date};
+use crate::parsers::ordinals::{nth_weekdays, ordinal_days};
+use crate::parsers::locale::{named_dates, named_months, numeric_dates, parse_day_month, parse_my, parse_ymd, spelled_dates_uk, spelled_dates_us, week_numbers};
 use crate::parsers::relative::{
     relative_date_past,
-    relative_date_future, relative_weekdays, current_weekdays
+    relative_date_future, relative_weekdays, current_weekdays, relative_periods,
+    relative_months, week_weekdays, weekends
 };
+use crate::utils::{next_day_on_weekday, weekday_string_to_int, weekday_to_int};
 
 pub fn dates(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, DateTime<Local>, ()> + '_ {
     move |input: &str| {
-        let parse_uk_or_us = match config.dialect {
-            Dialect::UK => parse_dmy,
-            Dialect::US => parse_mdy
-        };
-
-        let parse_spelled_uk_or_us = match config.dialect {
-            Dialect::UK => spelled_dates_uk,
-            Dialect::US => spelled_dates_us
-        };
-
         let res = alt((
-            parse_iso_date,
-            parse_uk_or_us,
-            parse_spelled_uk_or_us,
+            weekday_dates(config),
+            alt((
+                parse_iso_dates,
+                parse_ymd,
+                numeric_dates(config),
+                parse_iso_reduced_date
+            )),
+            // Spelled months are not ambiguous, so both orders are accepted
+            spelled_dates_uk(config),
+            spelled_dates_us(config),
             parse_my,
-            named_dates,
-            named_months,
+            parse_day_month(config),
+            named_dates(config),
+            named_months(config),
             relative_date_past,
             relative_date_future,
-            relative_weekdays,
-            current_weekdays
+            map(relative_periods(config), |(start, _)| start),
+            map(relative_months, |(start, _)| start),
+            map(weekends(config), |(start, _)| start),
+            map(week_numbers(config), |(start, _)| start),
+            period_boundaries(config),
+            nth_weekdays(config),
+            week_weekdays(config),
+            relative_weekdays(config),
+            current_weekdays(config),
+            ordinal_days(config)
         ))(input)?;
 
         Ok(res)
     }
 }
+
+/* Parse dates preceded by their weekday, like "fri, 13 sep 2024"
+ * or "monday the 15th". Without a month or a year, the weekday
+ * chooses the next matching date */
+fn weekday_dates(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, DateTime<Local>, ()> + '_ {
+    move |input: &str| {
+        let (tail, (day, _, _, (dt, months_opt))) = tuple((
+            terminated(weekday, word_end),
+            opt(tag(",")),
+            space1,
+            alt((
+                map(
+                    alt((
+                        parse_iso_dates,
+                        parse_ymd,
+                        numeric_dates(config),
+                        spelled_dates_uk(config),
+                        spelled_dates_us(config)
+                    )),
+                    |dt| (dt, None)
+                ),
+                map(parse_day_month(config), |dt| (dt, Some(12))),
+                map(ordinal_days(config), |dt| (dt, Some(1)))
+            ))
+        )).parse(input)?;
+
+        let weekday = weekday_string_to_int(day)
+            .map_err(|_| nom::Err::<()>::Error(()))?;
+
+        if let Some(months) = months_opt {
+            let dt = next_day_on_weekday(dt.date_naive(), weekday, months)?;
+            return Ok((tail, dt));
+        }
+
+        // A failure, rather than an error, stops other parsers
+        // from reading the weekday on its own
+        if config.weekday_check == WeekdayCheck::Fail && weekday != weekday_to_int(dt.weekday()) {
+            return Err(nom::Err::Failure(()));
+        }
+
+        Ok((tail, dt))
+    }
+}
@@ -1,34 +1,99 @@
 use chrono::prelude::*;
-use nom::IResult;
+use chrono::Duration;
+use nom::{Parser, IResult};
 use nom::branch::alt;
 use nom::character::complete::space1;
-use nom::combinator::map_res;
-use nom::sequence::tuple;
+use nom::combinator::{map_res, opt};
+use nom::sequence::{preceded, terminated, tuple};
 use nom::bytes::complete::tag;
 use crate::ParseConfig;
 
+use crate::parsers::generic::word_end;
 use crate::parsers::iso::parse_iso;
+use crate::parsers::locale::{connector, hour_preposition, parse_bare_hour};
 use crate::parsers::combined::dates;
-use crate::parsers::combined::times;
-use crate::utils::join_date_time;
+use crate::parsers::combined::times_of_day;
+use crate::utils::{extract_datetime, join_date_time, period_bounds, weekday_in_week, weekday_to_int};
 
 pub fn full_datetime(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, DateTime<Local>, ()> + '_ {
     move |input: &str| {
         let (tail, dt) = alt((
             parse_iso,
+            deadlines(config),
             map_res(
                 tuple((
                     dates(config),
-                    alt((
-                        tag(" at "),
-                        space1
-                    )),
-                    times
+                    connector(config),
+                    times_of_day(config)
                 )),
-                |(date, _, time)| join_date_time(date, time)
+                |(date, _, (time, day))| join_date_time(date, time, day)
+            ),
+            map_res(
+                tuple((
+                    dates(config),
+                    preceded(space1, hour_preposition(config)),
+                    parse_bare_hour(config)
+                )),
+                |(date, _, time)| join_date_time(date, time, Local::now().date_naive())
+            ),
+            map_res(
+                tuple((
+                    times_of_day(config),
+                    connector(config),
+                    dates(config)
+                )),
+                |((time, day), _, date)| join_date_time(date, time, day)
             )
         ))(input)?;
 
         Ok((tail, dt))
     }
 }
+
+/* Business deadlines, like "eod", "cob tomorrow" or "eow" */
+pub fn deadlines(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, DateTime<Local>, ()> + '_ {
+    move |input: &str| {
+        let (tail, (deadline, date_opt)) = tuple((
+            terminated(
+                alt((
+                    tag("eod"),
+                    tag("cob"),
+                    tag("close of business"),
+                    tag("end of business"),
+                    tag("eow")
+                )),
+                word_end
+            ),
+            opt(preceded(
+                alt((
+                    tag(" on "),
+                    space1
+                )),
+                dates(config)
+            ))
+        )).parse(input)?;
+
+        let mut day = date_opt.unwrap_or_else(|| Local::now().round_subsecs(0));
+
+        if deadline == "eow" {
+            // The working week is considered to end on friday
+            let (week_start, _) = period_bounds(day, "week", 0, config.week_start)?;
+            let to_friday = weekday_in_week(weekday_to_int(Weekday::Fri), config.week_start);
+            day = week_start + Duration::days(to_friday);
+        }
+
+        let end = config.business_day_end;
+        let dt_opt = Local.with_ymd_and_hms(
+            day.year(),
+            day.month(),
+            day.day(),
+            end.hour(),
+            end.minute(),
+            end.second()
+        );
+
+        let dt = extract_datetime(dt_opt)?;
+
+        Ok((tail, dt))
+    }
+}
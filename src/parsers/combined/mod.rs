@@ -1,7 +1,9 @@
 pub mod dates;
 pub mod times;
 mod datetimes;
+mod ranges;
 
 pub use dates::dates;
-pub use times::times;
+pub use times::{times, times_of_day};
 pub use datetimes::full_datetime;
+pub use ranges::ranges;
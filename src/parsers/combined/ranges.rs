@@ -0,0 +1,43 @@
+use chrono::prelude::*;
+use nom::IResult;
+use nom::branch::alt;
+use nom::character::complete::space1;
+use nom::combinator::{map_res, opt};
+use nom::sequence::{terminated, tuple};
+use crate::ParseConfig;
+
+use crate::parsers::combined::dates;
+use crate::parsers::locale::{day_periods, named_months, week_numbers};
+use crate::parsers::relative::{relative_months, relative_periods, weekends};
+use crate::utils::{naive_to_local, period_bounds};
+
+pub fn ranges(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, (DateTime<Local>, DateTime<Local>), ()> + '_ {
+    move |input: &str| {
+        let res = alt((
+            relative_periods(config),
+            relative_months,
+            weekends(config),
+            week_numbers(config),
+            map_res(
+                named_months(config),
+                |start| period_bounds(start, "month", 0, config.week_start)
+            ),
+            map_res(
+                tuple((
+                    opt(terminated(dates(config), space1)),
+                    day_periods(config)
+                )),
+                |(date_opt, (day, start, end))| {
+                    let day = date_opt.map_or(day, |date| date.date_naive());
+
+                    Ok::<_, nom::Err<()>>((
+                        naive_to_local(day, start.hour(), start.minute(), start.second())?,
+                        naive_to_local(day, end.hour(), end.minute(), end.second())?
+                    ))
+                }
+            )
+        ))(input)?;
+
+        Ok(res)
+    }
+}
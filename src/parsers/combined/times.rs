@@ -1,20 +1,61 @@
 use chrono::prelude::*;
 use nom::IResult;
 use nom::branch::alt;
+use nom::combinator::{map, map_res};
+use nom::sequence::preceded;
+use crate::ParseConfig;
 
 use crate::parsers::iso::parse_iso_time;
 use crate::parsers::locale::{
+    day_periods,
+    hour_preposition,
+    parse_bare_hour,
     parse_time,
+    parse_time_military,
+    parse_time_period,
     parse_time_ampm, parse_time_spelled
 };
+use crate::utils::{logical_today, naive_to_local, resolve_time};
 
-pub fn times(input: &str) -> IResult<&str, DateTime<Local>, ()> {
-    let res = alt((
-        parse_iso_time,
-        parse_time,
-        parse_time_ampm,
-        parse_time_spelled
-    ))(input)?;
+pub fn times(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, DateTime<Local>, ()> + '_ {
+    move |input: &str| {
+        let (tail, (dt, day)) = times_of_day(config)(input)?;
 
-    Ok(res)
+        // Periods of an earlier day, like "last night", already name their day
+        if day < logical_today(config.day_rollover_hour)? {
+            return Ok((tail, dt));
+        }
+
+        let dt = resolve_time(Local::now(), dt, &config.day_of_time)?;
+
+        Ok((tail, dt))
+    }
+}
+
+/* Parse a time without applying `day_of_time`, along with the day it
+ * was given for, to be joined with a date */
+pub fn times_of_day(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, (DateTime<Local>, NaiveDate), ()> + '_ {
+    move |input: &str| {
+        let res = alt((
+            parse_time_period(config),
+            map(
+                alt((
+                    parse_iso_time,
+                    parse_time_military,
+                    parse_time,
+                    parse_time_ampm,
+                    parse_time_spelled,
+                    preceded(hour_preposition(config), parse_bare_hour(config))
+                )),
+                |dt| (dt, Local::now().date_naive())
+            ),
+            map_res(
+                day_periods(config),
+                |(day, start, _)| naive_to_local(day, start.hour(), start.minute(), start.second())
+                    .map(|dt| (dt, day))
+            )
+        ))(input)?;
+
+        Ok(res)
+    }
 }
@@ -2,11 +2,17 @@
 use nom::{Parser, IResult};
 use nom::branch::alt;
 use nom::bytes::complete::{tag, take, take_while, take_while_m_n};
-use nom::combinator::map_res;
+use nom::character::complete::{one_of, satisfy};
+use nom::combinator::{map_res, not, opt, value};
+use nom::sequence::{preceded, terminated, tuple};
 
-pub fn year(input: &str) -> IResult<&str, i32, ()> {
+/* Parse a year written with exactly four digits */
+pub fn year4(input: &str) -> IResult<&str, i32, ()> {
     map_res(
-        take_while(|c| char::is_digit(c, 10)),
+        terminated(
+            take_while_m_n(4, 4, |c| char::is_digit(c, 10)),
+            not(satisfy(|c| char::is_digit(c, 10)))
+        ),
         |y: &str| y.parse::<i32>()
     ).parse(input)
 }
@@ -83,14 +89,28 @@ pub fn second2(input: &str) -> IResult<&str, u32, ()> {
     ).parse(input)
 }
 
-#[allow(dead_code)]
+/* Parse the decimal part of a second, like "5" or "123" in
+ * "10:30:15.123", as milliseconds. Further digits are ignored */
 pub fn millisecond(input: &str) -> IResult<&str, u32, ()> {
     map_res(
-        take(3u8),
-        |ms: &str| ms.parse::<u32>()
+        terminated(
+            take_while_m_n(1, 3, |c| char::is_digit(c, 10)),
+            take_while(|c| char::is_digit(c, 10))
+        ),
+        |ms: &str| ms.parse::<u32>().map(|n| n * 10u32.pow(3 - ms.len() as u32))
     ).parse(input)
 }
 
+/* Parse the character between the numbers of a date, like "/" or "." */
+pub fn date_separator(input: &str) -> IResult<&str, char, ()> {
+    one_of("/.- ").parse(input)
+}
+
+/* Succeeds without consuming anything if the current word has ended */
+pub fn word_end(input: &str) -> IResult<&str, (), ()> {
+    not(satisfy(char::is_alphanumeric)).parse(input)
+}
+
 pub fn weekday(input: &str) -> IResult<&str, &str, ()> {
     alt((
         alt((
@@ -145,3 +165,84 @@ pub fn month_name(input: &str) -> IResult<&str, &str, ()> {
         ))
     )).parse(input)
 }
+
+/* Parse an ordinal between first and fifth, or "last" (returned as -1) */
+pub fn ordinal(input: &str) -> IResult<&str, i32, ()> {
+    map_res(
+        alt((
+            alt((
+                tag("first"),
+                tag("second"),
+                tag("third"),
+                tag("fourth"),
+                tag("fifth"),
+                tag("last")
+            )),
+            alt((
+                tag("1st"),
+                tag("2nd"),
+                tag("3rd"),
+                tag("4th"),
+                tag("5th")
+            ))
+        )),
+        |o: &str| match o {
+            "first" | "1st" => Ok(1),
+            "second" | "2nd" => Ok(2),
+            "third" | "3rd" => Ok(3),
+            "fourth" | "4th" => Ok(4),
+            "fifth" | "5th" => Ok(5),
+            "last" => Ok(-1),
+            _ => Err(())
+        }
+    ).parse(input)
+}
+
+/* Parse a number between one and fifty-nine written in words */
+pub fn number_word(input: &str) -> IResult<&str, u32, ()> {
+    let units = |input| alt((
+        value(1, tag("one")),
+        value(2, tag("two")),
+        value(3, tag("three")),
+        value(4, tag("four")),
+        value(5, tag("five")),
+        value(6, tag("six")),
+        value(7, tag("seven")),
+        value(8, tag("eight")),
+        value(9, tag("nine"))
+    )).parse(input);
+
+    let teens = alt((
+        value(10, tag("ten")),
+        value(11, tag("eleven")),
+        value(12, tag("twelve")),
+        value(13, tag("thirteen")),
+        value(14, tag("fourteen")),
+        value(15, tag("fifteen")),
+        value(16, tag("sixteen")),
+        value(17, tag("seventeen")),
+        value(18, tag("eighteen")),
+        value(19, tag("nineteen"))
+    ));
+
+    let tens = map_res(
+        tuple((
+            alt((
+                value(20, tag("twenty")),
+                value(30, tag("thirty")),
+                value(40, tag("forty")),
+                value(50, tag("fifty"))
+            )),
+            opt(preceded(
+                alt((tag("-"), tag(" "))),
+                units
+            ))
+        )),
+        |(tens, units_opt): (u32, Option<u32>)| Ok::<u32, ()>(tens + units_opt.unwrap_or(0))
+    );
+
+    terminated(
+        alt((tens, teens, units)),
+        word_end
+    ).parse(input)
+}
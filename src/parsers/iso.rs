@@ -1,15 +1,19 @@
 use chrono::prelude::*;
+use chrono::Duration;
 use nom::{Parser, IResult};
-use nom::bytes::complete::tag;
-use nom::combinator::opt;
-use nom::sequence::tuple;
+use nom::branch::alt;
+use nom::bytes::complete::{tag, tag_no_case, take_while_m_n};
+use nom::character::complete::{digit1, one_of, satisfy};
+use nom::combinator::{map_res, not, opt};
+use nom::sequence::{preceded, separated_pair, terminated, tuple};
 
+use crate::{IsoDuration, Recurrence};
 use crate::parsers::generic::*;
-use crate::utils::extract_datetime;
+use crate::utils::{extract_datetime, join_date_time, naive_to_local, today_at};
 
 pub fn parse_iso_date(input: &str) -> IResult<&str, DateTime<chrono::Local>, ()> {
     let (tail, (year, _, month, _, day)) = tuple((
-        year,
+        year4,
         tag("-"),
         month2,
         tag("-"),
@@ -23,7 +27,97 @@ pub fn parse_iso_date(input: &str) -> IResult<&str, DateTime<chrono::Local>, ()>
     Ok((tail, dt))
 }
 
-// TODO: Add check for microseconds and timezones
+/* Parse dates in the ISO basic format, like "20240612" */
+pub fn parse_iso_date_basic(input: &str) -> IResult<&str, DateTime<chrono::Local>, ()> {
+    let (tail, (year, month, day, _)) = tuple((
+        basic_year,
+        month2,
+        day2,
+        not(satisfy(|c| char::is_digit(c, 10)))
+    )).parse(input)?;
+
+    let dt_opt = Local.with_ymd_and_hms(year, month, day, 0, 0, 0);
+
+    let dt = extract_datetime(dt_opt)?;
+
+    Ok((tail, dt))
+}
+
+/* Parse ISO ordinal dates, like "2024-157" or "2024157" */
+pub fn parse_iso_ordinal_date(input: &str) -> IResult<&str, DateTime<chrono::Local>, ()> {
+    let (tail, (year, _, day, _)) = tuple((
+        basic_year,
+        opt(tag("-")),
+        map_res(
+            take_while_m_n(3, 3, |c| char::is_digit(c, 10)),
+            |d: &str| d.parse::<u32>()
+        ),
+        not(satisfy(|c| char::is_digit(c, 10)))
+    )).parse(input)?;
+
+    let date = NaiveDate::from_yo_opt(year, day).ok_or(nom::Err::Error(()))?;
+
+    let dt = naive_to_local(date, 0, 0, 0)?;
+
+    Ok((tail, dt))
+}
+
+/* Parse ISO week dates, like "2024-W23-3" or "2024W233". Without
+ * the day of the week, the monday of that week is returned */
+pub fn parse_iso_week_date(input: &str) -> IResult<&str, DateTime<chrono::Local>, ()> {
+    let (tail, (year, _, _, week, day_opt, _)) = tuple((
+        basic_year,
+        opt(tag("-")),
+        alt((tag("W"), tag("w"))),
+        month2,
+        opt(preceded(
+            opt(tag("-")),
+            map_res(one_of("1234567"), |d| d.to_string().parse::<u32>())
+        )),
+        not(satisfy(|c| char::is_digit(c, 10) || c == '-'))
+    )).parse(input)?;
+
+    let weekday = Weekday::try_from(day_opt.unwrap_or(1) as u8 - 1)
+        .map_err(|_| nom::Err::Error(()))?;
+
+    let date = NaiveDate::from_isoywd_opt(year, week, weekday).ok_or(nom::Err::Error(()))?;
+
+    let dt = naive_to_local(date, 0, 0, 0)?;
+
+    Ok((tail, dt))
+}
+
+/* Parse ISO dates with reduced precision, like "2024-06" or "2024",
+ * returning the first day they refer to */
+pub fn parse_iso_reduced_date(input: &str) -> IResult<&str, DateTime<chrono::Local>, ()> {
+    let (tail, (year, month_opt, _, _, _)) = tuple((
+        year4,
+        opt(preceded(tag("-"), month2)),
+        word_end,
+        // Leave other dates, like "2024/06/12", to their own parsers
+        not(tuple((one_of("/.-"), digit1))),
+        // and invalid week dates, like "2024-w53-1", to fail
+        not(tuple((opt(tag("-")), alt((tag("W"), tag("w"))), digit1)))
+    )).parse(input)?;
+
+    let dt_opt = Local.with_ymd_and_hms(year, month_opt.unwrap_or(1), 1, 0, 0, 0);
+
+    let dt = extract_datetime(dt_opt)?;
+
+    Ok((tail, dt))
+}
+
+/* Parse any complete ISO date */
+pub fn parse_iso_dates(input: &str) -> IResult<&str, DateTime<chrono::Local>, ()> {
+    alt((
+        parse_iso_date,
+        parse_iso_week_date,
+        parse_iso_ordinal_date,
+        parse_iso_date_basic
+    )).parse(input)
+}
+
+// TODO: Add check for timezones
 pub fn parse_iso_time(input: &str) -> IResult<&str, DateTime<chrono::Local>, ()> {
     let (tail, data) = tuple((
         hour2,
@@ -33,52 +127,185 @@ pub fn parse_iso_time(input: &str) -> IResult<&str, DateTime<chrono::Local>, ()>
             tuple((
                 tag(":"),
                 second2,
+                opt(preceded(one_of(".,"), millisecond))
             ))
         )
     )).parse(input)?;
 
     let (hour, _, minute, opt_sec) = data;
 
-    let mut second = 0;
+    let (second, ms) = match opt_sec {
+        Some((_, sec, ms_opt)) => (sec, ms_opt.unwrap_or(0)),
+        None => (0, 0)
+    };
 
-    if let Some((_, sec)) = opt_sec {
-        second = sec;
-    }
+    let dt = with_millisecond(today_at(hour, minute, second)?, ms)?;
 
-    let now = Local::now().round_subsecs(0);
-    let dt_opt = Local.with_ymd_and_hms(
-        now.year(),
-        now.month(),
-        now.day(),
-        hour,
-        minute,
-        second
-    );
+    Ok((tail, dt))
+}
 
-    let dt = extract_datetime(dt_opt)?;
+/* Parse times in the ISO basic format, like "070224" or "0702",
+ * as found after the "T" of a date */
+pub fn parse_iso_time_basic(input: &str) -> IResult<&str, DateTime<chrono::Local>, ()> {
+    let (tail, (hour, minute, sec_opt, _)) = tuple((
+        hour2,
+        minute2,
+        opt(tuple((
+            second2,
+            opt(preceded(one_of(".,"), millisecond))
+        ))),
+        not(satisfy(|c| char::is_digit(c, 10)))
+    )).parse(input)?;
+
+    let (second, ms) = match sec_opt {
+        Some((sec, ms_opt)) => (sec, ms_opt.unwrap_or(0)),
+        None => (0, 0)
+    };
+
+    let dt = with_millisecond(today_at(hour, minute, second)?, ms)?;
 
     Ok((tail, dt))
 }
 
 pub fn parse_iso(input: &str) -> IResult<&str, DateTime<chrono::Local>, ()> {
-    let (tail, date) = parse_iso_date(input)?;
-    let (tail, _) = tag("T").parse(tail)?;
-    let (tail, time) = parse_iso_time(tail)?;
-
-    let dt_opt = Local.with_ymd_and_hms(
-        date.year(),
-        date.month(),
-        date.day(),
-        time.hour(),
-        time.minute(),
-        time.second()
-    );
+    let (tail, date) = parse_iso_dates(input)?;
+    let (tail, _) = alt((tag("T"), tag("t"), tag(" "))).parse(tail)?;
+    let (tail, time) = alt((parse_iso_time, parse_iso_time_basic)).parse(tail)?;
 
-    let dt = extract_datetime(dt_opt)?;
+    let dt = join_date_time(date, time, Local::now().date_naive())?;
 
     Ok((tail, dt))
 }
 
+/* Parse durations, like "p3y6m4dt12h30m5s", "pt15m" or "p2w" */
+pub fn iso_duration(input: &str) -> IResult<&str, IsoDuration, ()> {
+    let (tail, (_, years, months, weeks, days, time_opt)) = tuple((
+        tag_no_case("p"),
+        opt(duration_part("y")),
+        opt(duration_part("m")),
+        opt(duration_part("w")),
+        opt(duration_part("d")),
+        opt(preceded(
+            tag_no_case("t"),
+            tuple((
+                opt(duration_part("h")),
+                opt(duration_part("m")),
+                opt(duration_part("s"))
+            ))
+        ))
+    )).parse(input)?;
+
+    let (hours, minutes, seconds) = time_opt.unwrap_or((None, None, None));
+
+    // A "t" must be followed by a time, and the whole duration can't be empty
+    let has_time = hours.is_some() || minutes.is_some() || seconds.is_some();
+    let has_date = years.is_some() || months.is_some() || weeks.is_some() || days.is_some();
+
+    if !has_time && (time_opt.is_some() || !has_date) {
+        return Err(nom::Err::Error(()));
+    }
+
+    let duration = IsoDuration {
+        years: years.unwrap_or(0),
+        months: months.unwrap_or(0),
+        weeks: weeks.unwrap_or(0),
+        days: days.unwrap_or(0),
+        hours: hours.unwrap_or(0),
+        minutes: minutes.unwrap_or(0),
+        seconds: seconds.unwrap_or(0)
+    };
+
+    Ok((tail, duration))
+}
+
+/* Parse intervals, like "2024-01-01/2024-02-01", "2024-01-01/p1m"
+ * or "p1m/2024-02-01", returning their start and end */
+pub fn iso_interval(input: &str) -> IResult<&str, (DateTime<Local>, DateTime<Local>), ()> {
+    alt((
+        map_res(
+            separated_pair(interval_point, tag("/"), interval_point),
+            |(start, end)| if start <= end { Ok((start, end)) } else { Err(()) }
+        ),
+        map_res(
+            separated_pair(interval_point, tag("/"), iso_duration),
+            |(start, duration)| duration.add_to(start).map(|end| (start, end)).ok_or(())
+        ),
+        map_res(
+            separated_pair(iso_duration, tag("/"), interval_point),
+            |(duration, end)| duration.sub_from(end).map(|start| (start, end)).ok_or(())
+        )
+    )).parse(input)
+}
+
+/* Parse repeating intervals, like "r5/2024-01-01t09:00/p1w", where
+ * the number of repetitions can be left out to repeat forever */
+pub fn iso_recurrence(input: &str) -> IResult<&str, Recurrence, ()> {
+    let (tail, (_, repetitions, _)) = tuple((
+        tag_no_case("r"),
+        opt(map_res(digit1, |r: &str| r.parse::<u32>())),
+        tag("/")
+    )).parse(input)?;
+
+    let (tail, (start, period)) = alt((
+        separated_pair(interval_point, tag("/"), iso_duration),
+        map_res(
+            separated_pair(iso_duration, tag("/"), interval_point),
+            |(duration, end)| duration.sub_from(end).map(|start| (start, duration)).ok_or(())
+        ),
+        map_res(
+            separated_pair(interval_point, tag("/"), interval_point),
+            |(start, end)| exact_duration(end - start).map(|period| (start, period)).ok_or(())
+        )
+    )).parse(tail)?;
+
+    Ok((tail, Recurrence { repetitions, start, period }))
+}
+
+/* Parse a number followed by its designator, like "3y" in a duration */
+fn duration_part(designator: &'static str) -> impl Fn(&str) -> IResult<&str, u32, ()> {
+    move |input: &str| {
+        terminated(
+            map_res(digit1, |n: &str| n.parse::<u32>()),
+            tag_no_case(designator)
+        ).parse(input)
+    }
+}
+
+/* The start or end of an interval, with or without a time */
+fn interval_point(input: &str) -> IResult<&str, DateTime<Local>, ()> {
+    alt((parse_iso, parse_iso_dates)).parse(input)
+}
+
+/* Split the distance between two datetimes into days, hours, minutes
+ * and seconds, which have the same length anywhere in the calendar */
+fn exact_duration(duration: Duration) -> Option<IsoDuration> {
+    if duration <= Duration::zero() {
+        return None;
+    }
+
+    let seconds = u32::try_from(duration.num_seconds()).ok()?;
+
+    Some(IsoDuration {
+        days: seconds / 86400,
+        hours: seconds % 86400 / 3600,
+        minutes: seconds % 3600 / 60,
+        seconds: seconds % 60,
+        ..Default::default()
+    })
+}
+
+/* Four digits, even if followed by others, as in "20240612" */
+fn basic_year(input: &str) -> IResult<&str, i32, ()> {
+    map_res(
+        take_while_m_n(4, 4, |c| char::is_digit(c, 10)),
+        |y: &str| y.parse::<i32>()
+    ).parse(input)
+}
+
+fn with_millisecond(dt: DateTime<Local>, ms: u32) -> Result<DateTime<Local>, nom::Err<()>> {
+    dt.with_nanosecond(ms * 1_000_000).ok_or(nom::Err::Error(()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -99,6 +326,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_iso_end_of_day_test() {
+        let (_, dt) = parse_iso_time("24:00").unwrap();
+        assert!(
+            dt.date_naive() == Local::now().date_naive() + chrono::Duration::days(1) &&
+            dt.hour() == 0 &&
+            dt.minute() == 0 &&
+            dt.second() == 0
+        );
+
+        assert!(parse_iso_time("24:01").is_err());
+    }
+
     #[test]
     fn parse_iso_date_test() {
         let (_, dt) = parse_iso_date("2024-10-23").unwrap();
@@ -109,12 +349,105 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_iso_date_basic_test() {
+        let (_, dt) = parse_iso_date_basic("20240612").unwrap();
+        assert!(
+            dt.year() == 2024 &&
+            dt.month() == 6 &&
+            dt.day() == 12
+        );
+
+        assert!(parse_iso_date_basic("202406121").is_err());
+        assert!(parse_iso_date_basic("20241312").is_err());
+    }
+
+    #[test]
+    fn parse_iso_other_dates_test() {
+        let (_, dt) = parse_iso_ordinal_date("2024-157").unwrap();
+        assert!(dt.month() == 6 && dt.day() == 5);
+
+        let (_, dt) = parse_iso_ordinal_date("2024157").unwrap();
+        assert!(dt.month() == 6 && dt.day() == 5);
+
+        let (_, dt) = parse_iso_week_date("2024-w23-3").unwrap();
+        assert!(dt.month() == 6 && dt.day() == 5);
+
+        let (_, dt) = parse_iso_week_date("2024w23").unwrap();
+        assert!(dt.month() == 6 && dt.day() == 3);
+
+        let (_, dt) = parse_iso_reduced_date("2024-06").unwrap();
+        assert!(dt.year() == 2024 && dt.month() == 6 && dt.day() == 1);
+
+        let (_, dt) = parse_iso_reduced_date("2024").unwrap();
+        assert!(dt.year() == 2024 && dt.month() == 1 && dt.day() == 1);
+
+        assert!(parse_iso_ordinal_date("2023-366").is_err());
+        assert!(parse_iso_week_date("2024-w23-8").is_err());
+        assert!(parse_iso_reduced_date("2024/06/12").is_err());
+
+        // 2024 has only 52 weeks
+        assert!(parse_iso_week_date("2024-w53-1").is_err());
+        assert!(parse_iso_reduced_date("2024-w53-1").is_err());
+        assert!(parse_iso_reduced_date("2024w53").is_err());
+    }
+
+    #[test]
+    fn parse_iso_datetime_test() {
+        let (_, dt) = parse_iso("20240605t070224").unwrap();
+        assert!(
+            dt.day() == 5 &&
+            dt.hour() == 7 &&
+            dt.minute() == 2 &&
+            dt.second() == 24
+        );
+
+        let (_, dt) = parse_iso("2024-06-05 07:02:24.5").unwrap();
+        assert!(
+            dt.second() == 24 &&
+            dt.timestamp_subsec_millis() == 500
+        );
+
+        let (_, dt) = parse_iso("2024-06-05t07:02:24,123456").unwrap();
+        assert!(dt.timestamp_subsec_millis() == 123);
+
+        let (_, dt) = parse_iso("2024-06-05t24:00").unwrap();
+        assert!(
+            dt.day() == 6 &&
+            dt.hour() == 0 &&
+            dt.minute() == 0
+        );
+    }
+
+    #[test]
+    fn iso_duration_test() {
+        let (_, duration) = iso_duration("p1y2m3w4dt5h6m7s").unwrap();
+        assert!(duration == IsoDuration {
+            years: 1,
+            months: 2,
+            weeks: 3,
+            days: 4,
+            hours: 5,
+            minutes: 6,
+            seconds: 7
+        });
+
+        // The "m" after "t" is minutes, not months
+        let (_, duration) = iso_duration("pt1m").unwrap();
+        assert!(duration.minutes == 1 && duration.months == 0);
+
+        assert!(iso_duration("p").is_err());
+        assert!(iso_duration("pt").is_err());
+        assert!(iso_duration("1d").is_err());
+    }
+
     #[test]
     fn parse_wrong_iso_date() {
-        let mut results = vec![];
-        results.push(parse_iso_date("12/12/2024"));
-        results.push(parse_iso_date("2024-2-1"));
-        results.push(parse_iso_date("2024-33-33"));
+        let results = vec![
+            parse_iso_date("12/12/2024"),
+            parse_iso_date("2024-2-1"),
+            parse_iso_date("2024-33-33"),
+        ];
 
         assert!(
             results.into_iter().all(|r| r.is_err())
@@ -123,9 +456,10 @@ mod tests {
 
     #[test]
     fn parse_wrong_iso_time() {
-        let mut results = vec![];
-        results.push(parse_iso_time("1:2"));
-        results.push(parse_iso_time("10"));
+        let results = [
+            parse_iso_time("1:2"),
+            parse_iso_time("10"),
+        ];
 
         assert!(
             results.iter().all(|r| r.is_err()),
@@ -1,37 +1,126 @@
 use chrono::prelude::*;
 use chrono::Duration;
 use nom::branch::alt;
-use nom::character::complete::space0;
+use nom::character::complete::{space0, space1};
+use nom::sequence::{preceded, separated_pair};
 use nom::sequence::terminated;
 use nom::sequence::tuple;
 use nom::{Parser, IResult};
-use nom::bytes::complete::tag;
-use nom::combinator::opt;
+use nom::bytes::complete::{tag, take_while_m_n};
+use nom::character::complete::{char, digit1, one_of, satisfy};
+use nom::combinator::{map, map_res, not, opt, value};
 
+use crate::{DayPeriod, Dialect, ParseConfig};
 use crate::parsers::generic::*;
-use crate::utils::extract_datetime;
+use crate::parsers::relative::relative_year;
+use crate::utils::{expand_year, extract_datetime, extract_utc, hour_in_span, logical_today, today_at, naive_to_local, resolve_month, resolve_year, week_number_start};
 use crate::utils::month_string_to_int;
 
-pub fn parse_dmy(input: &str) -> IResult<&str, DateTime<Local>, ()> {
-    let (tail, day) = day1(input)?;
-    let (tail, _) = tag("/").parse(tail)?;
-    let (tail, month) = month1(tail)?;
-    let (tail, _) = tag("/").parse(tail)?;
-    let (tail, year) = year(tail)?;
+pub fn parse_dmy(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, DateTime<Local>, ()> + '_ {
+    move |input: &str| {
+        let (tail, day) = day1(input)?;
+        let (tail, sep) = date_separator(tail)?;
+        let (tail, month) = month1(tail)?;
+        let (tail, _) = char(sep).parse(tail)?;
+        let (tail, year) = year_digits(config)(tail)?;
 
-    let dt_opt = Local.with_ymd_and_hms(year, month, day, 0, 0, 0);
+        let dt_opt = Local.with_ymd_and_hms(year, month, day, 0, 0, 0);
 
-    let dt = extract_datetime(dt_opt)?;
+        let dt = extract_datetime(dt_opt)?;
 
-    Ok((tail, dt))
+        Ok((tail, dt))
+    }
 }
 
-pub fn parse_mdy(input: &str) -> IResult<&str, DateTime<Local>, ()> {
-    let (tail, month) = month1(input)?;
-    let (tail, _) = tag("/").parse(tail)?;
+pub fn parse_mdy(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, DateTime<Local>, ()> + '_ {
+    move |input: &str| {
+        let (tail, month) = month1(input)?;
+        let (tail, sep) = date_separator(tail)?;
+        let (tail, day) = day1(tail)?;
+        let (tail, _) = char(sep).parse(tail)?;
+        let (tail, year) = year_digits(config)(tail)?;
+
+        let dt_opt = Local.with_ymd_and_hms(year, month, day, 0, 0, 0);
+
+        let dt = extract_datetime(dt_opt)?;
+
+        Ok((tail, dt))
+    }
+}
+
+/* Parse numeric dates, like "12/06/2024", in the order of the dialect */
+pub fn numeric_dates(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, DateTime<Local>, ()> + '_ {
+    move |input: &str| {
+        match config.dialect {
+            Dialect::UK => parse_dmy(config)(input),
+            Dialect::US => parse_mdy(config)(input)
+        }
+    }
+}
+
+/* Parse a year written with four digits, or with two digits
+ * as in "24" or "'24", completed using the configuration */
+pub fn year_digits(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, i32, ()> + '_ {
+    move |input: &str| {
+        alt((
+            year4,
+            preceded(opt(char('\'')), two_digit_year(config))
+        )).parse(input)
+    }
+}
+
+pub fn two_digit_year(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, i32, ()> + '_ {
+    move |input: &str| {
+        let (tail, (year, _, _)) = tuple((
+            map_res(
+                take_while_m_n(2, 2, |c: char| c.is_ascii_digit()),
+                |y: &str| y.parse::<i32>()
+            ),
+            word_end,
+            // Leave times like "june 5 12:30" alone
+            not(tuple((one_of(":."), digit1)))
+        )).parse(input)?;
+
+        let year = expand_year(year, &config.two_digit_years, Local::now().year());
+
+        Ok((tail, year))
+    }
+}
+
+/* Parse dates without a year, like "12/06", using the dialect to
+ * decide which number is the day and which one is the month */
+pub fn parse_day_month(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, DateTime<Local>, ()> + '_ {
+    move |input: &str| {
+        // Dots are left out, since "12.06" is more likely a time
+        let (tail, (first, _, second, _, _)) = tuple((
+            day1,
+            one_of("/-"),
+            month1,
+            word_end,
+            // A year that couldn't be read makes the whole date invalid
+            not(tuple((one_of("/.-"), digit1)))
+        )).parse(input)?;
+
+        let (day, month) = match config.dialect {
+            Dialect::UK => (first, second),
+            Dialect::US => (second, first)
+        };
+
+        let now = Local::now().round_subsecs(0);
+        let dt = resolve_year(now, month, day, &config.year_of_date)?;
+
+        Ok((tail, dt))
+    }
+}
+
+/* Parse year-first dates, like "2024/06/12" or "2024.6.12" */
+pub fn parse_ymd(input: &str) -> IResult<&str, DateTime<Local>, ()> {
+    let (tail, year) = year4(input)?;
+    let (tail, sep) = date_separator(tail)?;
+    let (tail, month) = month1(tail)?;
+    let (tail, _) = char(sep).parse(tail)?;
     let (tail, day) = day1(tail)?;
-    let (tail, _) = tag("/").parse(tail)?;
-    let (tail, year) = year(tail)?;
+    let (tail, _) = word_end(tail)?;
 
     let dt_opt = Local.with_ymd_and_hms(year, month, day, 0, 0, 0);
 
@@ -42,8 +131,8 @@ pub fn parse_mdy(input: &str) -> IResult<&str, DateTime<Local>, ()> {
 
 pub fn parse_my(input: &str) -> IResult<&str, DateTime<Local>, ()> {
     let (tail, month) = month1(input)?;
-    let (tail, _) = tag("/").parse(tail)?;
-    let (tail, year) = year(tail)?;
+    let (tail, _) = one_of("/.-").parse(tail)?;
+    let (tail, year) = year4(tail)?;
 
     let dt_opt = Local.with_ymd_and_hms(year, month, 1, 0, 0, 0);
 
@@ -52,138 +141,425 @@ pub fn parse_my(input: &str) -> IResult<&str, DateTime<Local>, ()> {
     Ok((tail, dt))
 }
 
-pub fn named_dates(input: &str) -> IResult<&str, DateTime<Local>, ()> {
-    let (tail, data) = alt((
-        tag("yesterday"),
-        tag("tomorrow"),
-        tag("today")
-    )).parse(input)?;
+pub fn named_dates(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, DateTime<Local>, ()> + '_ {
+    move |input: &str| {
+        let (tail, data) = alt((
+            tag("yesterday"),
+            tag("tomorrow"),
+            tag("today")
+        )).parse(input)?;
+
+        let cur = Local::now().round_subsecs(0);
+
+        // Late at night, "tomorrow" can still be later today
+        let today = logical_today(config.day_rollover_hour)?;
 
-    let cur = Local::now().round_subsecs(0);
+        let offset = match data {
+            "yesterday" => -1,
+            "tomorrow" => 1,
+            "today" => 0,
+            _ => return Err(nom::Err::<()>::Error(()))
+        };
 
-    match data {
-        "yesterday" => Ok((tail, cur - Duration::days(1))),
-        "tomorrow" => Ok((tail, cur + Duration::days(1))),
-        "today" => Ok((tail, cur)),
-        _ => Err(nom::Err::<()>::Error(()))
+        // The current time is kept, only the date moves
+        let dt = naive_to_local(today + Duration::days(offset), cur.hour(), cur.minute(), cur.second())?;
+
+        Ok((tail, dt))
     }
 }
 
-pub fn spelled_dates_uk(input: &str) -> IResult<&str, DateTime<Local>, ()> {
-    let (tail, (day, _, month_str, year_opt)) = tuple((
-        terminated(
-            day1,
+pub fn spelled_dates_uk(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, DateTime<Local>, ()> + '_ {
+    move |input: &str| {
+        let (tail, (day, _, month_str, year_opt)) = tuple((
+            terminated(
+                day1,
+                opt(alt((
+                    tag("st"),
+                    tag("nd"),
+                    tag("rd"),
+                    tag("th")
+                )))
+            ),
+            tag(" "),
+            month_name,
             opt(alt((
-                tag("st"),
-                tag("nd"),
-                tag("rd"),
-                tag("th")
+                preceded(tag(" "), year_digits(config)),
+                relative_year
             )))
-        ),
-        tag(" "),
-        month_name,
-        opt(tuple((
+        )).parse(input)?;
+
+        let month = month_string_to_int(month_str)
+                    .map_err(|_| nom::Err::<()>::Error(()))?;
+
+        let dt = match year_opt {
+            Some(year) => extract_datetime(Local.with_ymd_and_hms(year, month, day, 0, 0, 0))?,
+            None => resolve_year(Local::now(), month, day, &config.year_of_date)?
+        };
+
+        Ok((tail, dt))
+    }
+}
+
+pub fn spelled_dates_us(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, DateTime<Local>, ()> + '_ {
+    move |input: &str| {
+        let (tail, (month_str, _, day, year_opt)) = tuple((
+            month_name,
             tag(" "),
-            year
-        )))
-    )).parse(input)?;
+            terminated(
+                day1,
+                opt(alt((
+                    tag("st"),
+                    tag("nd"),
+                    tag("rd"),
+                    tag("th")
+                )))
+            ),
+            opt(alt((
+                preceded(
+                    tuple((
+                        opt(tag(",")),
+                        tag(" ")
+                    )),
+                    year_digits(config)
+                ),
+                relative_year
+            )))
+        )).parse(input)?;
 
-    let month = month_string_to_int(month_str)
-                .map_err(|_| nom::Err::<()>::Error(()))?;
+        let month = month_string_to_int(month_str)
+                    .map_err(|_| nom::Err::<()>::Error(()))?;
 
-    let year = match year_opt {
-        Some((_, y)) => y,
-        None => Local::now().year()
-    };
+        let dt = match year_opt {
+            Some(year) => extract_datetime(Local.with_ymd_and_hms(year, month, day, 0, 0, 0))?,
+            None => resolve_year(Local::now(), month, day, &config.year_of_date)?
+        };
 
-    let dt_opt = Local.with_ymd_and_hms(year, month, day, 0, 0, 0);
-    let dt = extract_datetime(dt_opt)?;
+        Ok((tail, dt))
+    }
+}
 
-    Ok((tail, dt))
+/* Parse numbered weeks, like "week 23" or "week 23 of 2024" */
+pub fn week_numbers(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, (DateTime<Local>, DateTime<Local>), ()> + '_ {
+    move |input: &str| {
+        let (tail, (_, week, year_opt)) = tuple((
+            alt((
+                tag("week "),
+                tag("wk ")
+            )),
+            map_res(digit1, |w: &str| w.parse::<u32>()),
+            opt(preceded(
+                alt((
+                    tag(" of "),
+                    tag(", "),
+                    tag(" ")
+                )),
+                year4
+            ))
+        )).parse(input)?;
+
+        let year = year_opt.unwrap_or_else(|| Local::now().year());
+        let start = week_number_start(year, week, &config.week_numbering, config.week_start)?;
+        let end = naive_to_local(start.date_naive() + Duration::days(6), 23, 59, 59)?;
+
+        Ok((tail, (start, end)))
+    }
 }
 
-pub fn spelled_dates_us(input: &str) -> IResult<&str, DateTime<Local>, ()> {
-    let (tail, (month_str, _, day, year_opt)) = tuple((
-        month_name,
-        tag(" "),
-        terminated(
-            day1,
+pub fn named_months(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, DateTime<Local>, ()> + '_ {
+    move |input: &str| {
+        let (tail, (_, m_str, _, year_opt)) = tuple((
+            opt(tag("in ")),
+            month_name,
+            word_end,
             opt(alt((
-                tag("st"),
-                tag("nd"),
-                tag("rd"),
-                tag("th")
+                preceded(tag(" "), year4),
+                // A bare "jan 24" is more likely a day
+                preceded(tag(" '"), two_digit_year(config)),
+                relative_year
             )))
-        ),
-        opt(tuple((
-            tuple((
-                opt(tag(",")),
-                tag(" ")
-            )),
-            year
-        )))
-    )).parse(input)?;
+        )).parse(input)?;
 
-    let month = month_string_to_int(month_str)
-                .map_err(|_| nom::Err::<()>::Error(()))?;
+        let m = month_string_to_int(m_str)
+                    .map_err(|_| nom::Err::<()>::Error(()))?;
 
-    let year = match year_opt {
-        Some((_, y)) => y,
-        None => Local::now().year()
-    };
+        let dt = match year_opt {
+            Some(y) => extract_datetime(Local.with_ymd_and_hms(y, m, 1, 0, 0, 0))?,
+            None => resolve_month(Local::now(), m, &config.year_of_date)?
+        };
 
-    let dt_opt = Local.with_ymd_and_hms(year, month, day, 0, 0, 0);
-    let dt = extract_datetime(dt_opt)?;
+        Ok((tail, dt))
+    }
+}
 
-    Ok((tail, dt))
+/* Parse named times of the day, like "noon" or "this evening", using
+ * the table in the configuration. It returns the day they refer to
+ * ("last night" is yesterday) and their start and end time */
+pub fn day_periods(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, (NaiveDate, NaiveTime, NaiveTime), ()> + '_ {
+    move |input: &str| {
+        let (tail, rel_opt) = opt(alt((
+            tag("this "),
+            tag("last "),
+            tag("in the "),
+            tag("at ")
+        ))).parse(input)?;
+
+        let (name, period) = config.day_periods
+            .iter()
+            .filter(|(name, _)| tail.starts_with(name.as_str()))
+            .max_by_key(|(name, _)| name.len())
+            .ok_or(nom::Err::Error(()))?;
+
+        let (tail, _) = word_end(&tail[name.len()..])?;
+
+        let mut day = logical_today(config.day_rollover_hour)?;
+
+        if rel_opt == Some("last ") {
+            day -= Duration::days(1);
+        }
+
+        let (start, end) = match period {
+            DayPeriod::At(time) => (*time, *time),
+            DayPeriod::Between(start, end) => (*start, *end)
+        };
+
+        // Midnight on its own is the one at the end of the day
+        if let DayPeriod::At(time) = period {
+            if *time == NaiveTime::MIN {
+                day += Duration::days(1);
+            }
+        }
+
+        Ok((tail, (day, start, end)))
+    }
 }
 
-pub fn named_months(input: &str) -> IResult<&str, DateTime<Local>, ()> {
-    let (tail, m_str) = month_name(input)?;
-    let (tail, _) = tag(" ").parse(tail)?;
-    let (tail, y) = year(tail)?;
-    
-    let m = month_string_to_int(m_str)
-                .map_err(|_| nom::Err::<()>::Error(()))?;
+/* Parse a word from the configured connectors followed by
+ * a space, like "on " or "at about " */
+pub fn preposition(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, &str, ()> + '_ {
+    move |input: &str| {
+        let word = config.connectors
+            .iter()
+            .filter(|c| c.chars().any(char::is_alphanumeric))
+            .filter(|c| input.starts_with(c.as_str()))
+            .filter(|c| space1::<&str, ()>(&input[c.len()..]).is_ok())
+            .max_by_key(|c| c.len())
+            .ok_or(nom::Err::Error(()))?;
+
+        let (tail, _) = space1(&input[word.len()..])?;
+
+        Ok((tail, &input[..word.len()]))
+    }
+}
 
-    let dt_opt = Local.with_ymd_and_hms(y, m, 1, 0, 0, 0);
-    let dt = extract_datetime(dt_opt)?;
+/* Parse a connector that can lead a bare hour, like "at " in "at 3".
+ * Others, like "on" or "of", would turn any number into a time */
+pub fn hour_preposition(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, &str, ()> + '_ {
+    move |input: &str| {
+        let (tail, word) = preposition(config)(input)?;
 
-    Ok((tail, dt))
+        if !["at", "around", "at about"].contains(&word) {
+            return Err(nom::Err::Error(()));
+        }
+
+        Ok((tail, word))
+    }
 }
 
-pub fn parse_time(input: &str) -> IResult<&str, DateTime<Local>, ()> {
-    let (tail, data) = tuple((
-        hour1,
-        tag(":"),
-        minute1,
-        opt(
+/* Parse what separates a date from a time, like " at ", ", ",
+ * " @ " or just a space */
+pub fn connector(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, &str, ()> + '_ {
+    move |input: &str| {
+        if let Ok(res) = preceded(space1, preposition(config)).parse(input) {
+            return Ok(res);
+        }
+
+        let (tail, _) = space0(input)?;
+
+        let symbol_opt = config.connectors
+            .iter()
+            .filter(|c| !c.chars().any(char::is_alphanumeric))
+            .filter(|c| tail.starts_with(c.as_str()))
+            .max_by_key(|c| c.len());
+
+        match symbol_opt {
+            Some(symbol) => {
+                let (tail, _) = space0(&tail[symbol.len()..])?;
+                Ok((tail, &input[..input.len() - tail.len()]))
+            },
+            None => {
+                space1(input)
+            }
+        }
+    }
+}
+
+/* Parse hours whose meridiem comes from a period of the day, like
+ * "5 in the evening", "3 at night", "tonight at 8" or "dinner at 7",
+ * along with the day of the period */
+pub fn parse_time_period(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, (DateTime<Local>, NaiveDate), ()> + '_ {
+    move |input: &str| {
+        let (tail, ((hour, minute, ampm_opt), (day, start, end))) = alt((
+            map(
+                tuple((hour_minute_ampm, space1, day_periods(config))),
+                |(time, _, period)| (time, period)
+            ),
+            map(
+                tuple((day_periods(config), tag(" at "), hour_minute_ampm)),
+                |(period, _, time)| (time, period)
+            )
+        )).parse(input)?;
+
+        let hour = match ampm_opt {
+            Some(ampm) => apply_ampm(hour, ampm)?,
+            None if hour <= 12 => hour_in_span(hour, start.hour(), end.hour()),
+            None => hour
+        };
+
+        // Hours after midnight in the evening periods, like "tonight at 2",
+        // belong to the following day
+        let shifted_day = if hour < 12 && start.hour() >= 12 {
+            day + Duration::days(1)
+        } else {
+            day
+        };
+
+        let dt = naive_to_local(shifted_day, hour, minute, 0)?;
+
+        Ok((tail, (dt, day)))
+    }
+}
+
+/* Parse a lonely hour, like the 7 in "at 7", guessing whether it's
+ * am or pm with the `bare_hours_pm` option */
+pub fn parse_bare_hour(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, DateTime<Local>, ()> + '_ {
+    move |input: &str| {
+        let (tail, hour) = terminated(hour1, word_end).parse(input)?;
+
+        let hour = match config.bare_hours_pm {
+            Some((from, to)) if (from..=to).contains(&hour) && hour < 12 => hour + 12,
+            _ => hour
+        };
+
+        let dt = naive_to_local(Local::now().date_naive(), hour, 0, 0)?;
+
+        Ok((tail, dt))
+    }
+}
+
+fn hour_minute_ampm(input: &str) -> IResult<&str, (u32, u32, Option<&str>), ()> {
+    let (tail, ((hour, minute), ampm_opt, _)) = tuple((
+        alt((
+            spoken_clock,
             tuple((
-                tag(":"),
-                second1,
+                hour1,
+                map(
+                    tuple((
+                        opt(preceded(alt((tag(":"), tag("."))), minute2)),
+                        opt(tag(" o'clock"))
+                    )),
+                    |(minute_opt, _)| minute_opt.unwrap_or(0)
+                )
             ))
-        )
+        )),
+        opt(preceded(space0, ampm)),
+        word_end
     )).parse(input)?;
 
-    let (hour, _, minute, opt_sec) = data;
+    Ok((tail, (hour, minute, ampm_opt)))
+}
 
-    let mut second = 0;
+fn ampm(input: &str) -> IResult<&str, &str, ()> {
+    alt((
+        tag("a.m."),
+        tag("am"),
+        tag("p.m."),
+        tag("pm")
+    )).parse(input)
+}
+
+fn apply_ampm(hour: u32, ampm: &str) -> Result<u32, nom::Err<()>> {
+    if hour > 12 {
+        return Err(nom::Err::<()>::Error(()));
+    }
 
-    if let Some((_, sec)) = opt_sec {
-        second = sec;
+    match ampm {
+        "a.m." | "am" if hour == 12 => Ok(0),
+        "p.m." | "pm" if hour < 12 => Ok(hour + 12),
+        _ => Ok(hour)
     }
+}
 
-    let now = Local::now().round_subsecs(0);
-    let dt_opt = Local.with_ymd_and_hms(
-        now.year(),
-        now.month(),
-        now.day(),
-        hour,
-        minute,
-        second
-    );
+pub fn parse_time(input: &str) -> IResult<&str, DateTime<Local>, ()> {
+    let (tail, (hour, minute, second)) = terminated(
+        alt((
+            map(
+                tuple((
+                    hour1,
+                    tag(":"),
+                    minute1,
+                    opt(preceded(tag(":"), second1))
+                )),
+                |(hour, _, minute, opt_sec)| (hour, minute, opt_sec.unwrap_or(0))
+            ),
+            map(
+                separated_pair(hour1, tag("."), minute2),
+                |(hour, minute)| (hour, minute, 0)
+            ),
+            map(
+                tuple((
+                    hour1,
+                    tag("h"),
+                    opt(minute2),
+                    word_end
+                )),
+                |(hour, _, opt_min, _)| (hour, opt_min.unwrap_or(0), 0)
+            )
+        )),
+        tuple((
+            // Avoid reading the beginning of a date like 12.06.2024 as a time
+            not(tuple((one_of("./-"), digit1))),
+            // or the month of 06.2024
+            not(satisfy(|c| c.is_ascii_digit())),
+            // Leave times like 5:30pm to parse_time_ampm
+            not(preceded(space0, ampm))
+        ))
+    ).parse(input)?;
 
-    let dt = extract_datetime(dt_opt)?;
+    let dt = today_at(hour, minute, second)?;
+
+    Ok((tail, dt))
+}
+
+/* Parse military times, like "1730 hrs", "0900 hours" or "0900z".
+ * Without a suffix, only times with a leading zero are accepted,
+ * so that they can't be confused with years */
+pub fn parse_time_military(input: &str) -> IResult<&str, DateTime<Local>, ()> {
+    let (tail, (hour, minute, suffix_opt, _)) = tuple((
+        hour2,
+        minute2,
+        opt(alt((
+            tag(" hours"),
+            tag(" hrs"),
+            tag("hrs"),
+            tag("h"),
+            tag("z")
+        ))),
+        word_end
+    )).parse(input)?;
+
+    if suffix_opt.is_none() && !input.starts_with('0') {
+        return Err(nom::Err::<()>::Error(()));
+    }
+
+    let dt = match suffix_opt {
+        // Zulu time is UTC
+        Some("z") => {
+            let today = Local::now().date_naive();
+            let utc = extract_utc(Utc.with_ymd_and_hms(today.year(), today.month(), today.day(), hour, minute, 0))?;
+            utc.with_timezone(&Local)
+        },
+        _ => today_at(hour, minute, 0)?
+    };
 
     Ok((tail, dt))
 }
@@ -193,7 +569,10 @@ pub fn parse_time_ampm(input: &str) -> IResult<&str, DateTime<Local>, ()> {
         hour1,
         opt(
             tuple((
-                tag(":"),
+                alt((
+                    tag(":"),
+                    tag(".")
+                )),
                 minute1,
                 opt(
                     tuple((
@@ -204,31 +583,12 @@ pub fn parse_time_ampm(input: &str) -> IResult<&str, DateTime<Local>, ()> {
             ))
         ),
         space0,
-        alt((
-            tag("a.m."),
-            tag("am"),
-            tag("p.m."),
-            tag("pm")
-        ))
+        ampm
     )).parse(input)?;
 
     let (hour, opt_min_sec, _, ampm) = data;
 
-    if hour > 12 {
-        return Err(nom::Err::<()>::Error(()));
-    }
-
-    let mut hour = hour;
-
-    match ampm {
-        "a.m." | "am" => {
-            if hour == 12 { hour = 0 }
-        },
-        "p.m." | "pm" => {
-            if hour < 12 { hour += 12 }
-        },
-        _ => ()
-    }
+    let hour = apply_ampm(hour, ampm)?;
 
     let mut minute = 0;
     let mut second = 0;
@@ -268,46 +628,80 @@ pub fn parse_time_spelled(input: &str) -> IResult<&str, DateTime<Local>, ()> {
 
 fn parse_oclock(input: &str) -> IResult<&str, DateTime<Local>, ()> {
     let (tail, (hour, _)) = tuple((
-        hour1,
+        clock_hour,
         tag(" o'clock")
     )).parse(input)?;
 
-    let now = Local::now().round_subsecs(0);
-    let dt_opt = Local.with_ymd_and_hms(now.year(), now.month(), now.day(), hour, 0, 0);
-    let dt = extract_datetime(dt_opt)?;
+    let dt = today_at(hour, 0, 0)?;
 
     Ok((tail, dt))
 }
 
 fn parse_subminutes(input: &str) -> IResult<&str, DateTime<Local>, ()> {
-    let (tail, (amount, rel, hour)) = tuple((
-        alt((
-            tag("half "),
-            tag("a quarter ")
-        )),
-        alt((
-            tag("past "),
-            tag("to ")
-        )),
-        hour1
+    let (tail, ((hour, minute), ampm_opt)) = tuple((
+        spoken_clock,
+        opt(preceded(space0, ampm))
     )).parse(input)?;
 
-    let minutes = match amount {
-        "half " => 30,
-        "a quarter " => 15,
-        _ => 0 // this will never happen
+    let hour = match ampm_opt {
+        Some(ampm) => apply_ampm(hour, ampm)?,
+        None => hour
     };
 
-    let duration = match rel {
-        "past " => Duration::minutes(minutes),
-        "to " => Duration::minutes(-1 * minutes),
-        _ => Duration::minutes(0) // this will never happen
-    };
-
-    let now = Local::now();
-    let dt_opt = Local.with_ymd_and_hms(now.year(), now.month(), now.day(), hour, 0, 0);
-    let mut dt = extract_datetime(dt_opt)?;
-    dt += duration;
+    let dt = naive_to_local(Local::now().date_naive(), hour, minute, 0)?;
 
     Ok((tail, dt))
 }
+
+/* Parse spoken times, like "twenty past six", "a quarter to 10",
+ * "10 minutes to five" or the british "half six", returning the
+ * hour and the minutes */
+fn spoken_clock(input: &str) -> IResult<&str, (u32, u32), ()> {
+    alt((
+        map_res(
+            tuple((
+                alt((
+                    value(30, tag("half")),
+                    value(15, preceded(opt(tag("a ")), tag("quarter"))),
+                    terminated(
+                        alt((number_word, minute1)),
+                        opt(alt((
+                            tag(" minutes"),
+                            tag(" minute")
+                        )))
+                    )
+                )),
+                alt((
+                    tag(" past "),
+                    tag(" after "),
+                    tag(" to ")
+                )),
+                clock_hour
+            )),
+            |(minutes, rel, hour)| {
+                if minutes == 0 || minutes >= 60 || hour > 23 {
+                    return Err(());
+                }
+
+                match rel {
+                    " to " => Ok(((hour + 23) % 24, 60 - minutes)),
+                    _ => Ok((hour, minutes))
+                }
+            }
+        ),
+        map(
+            preceded(tag("half "), clock_hour),
+            |hour| (hour, 30)
+        )
+    )).parse(input)
+}
+
+fn clock_hour(input: &str) -> IResult<&str, u32, ()> {
+    terminated(
+        alt((
+            hour1,
+            map_res(number_word, |h| if h <= 12 { Ok(h) } else { Err(()) })
+        )),
+        word_end
+    ).parse(input)
+}
@@ -1,5 +1,8 @@
+pub mod boundaries;
 pub mod generic;
 pub mod iso;
 pub mod locale;
+pub mod ordinals;
 pub mod relative;
+pub mod rfc;
 pub mod combined;
@@ -0,0 +1,85 @@
+use chrono::prelude::*;
+use nom::{Parser, IResult};
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::space1;
+use nom::combinator::opt;
+use nom::sequence::{preceded, tuple};
+use crate::ParseConfig;
+
+use crate::parsers::boundaries::{calendar_year, current_period, month_of_year};
+use crate::parsers::generic::{day1, ordinal, weekday, word_end};
+use crate::utils::{nth_weekday_between, period_bounds, resolve_day_of_month, weekday_string_to_int};
+
+/* Parse dates like "second tuesday of march" or "the last friday of the month" */
+pub fn nth_weekdays(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, DateTime<Local>, ()> + '_ {
+    move |input: &str| {
+        let (tail, (_, nth, _, day, bounds_opt)) = tuple((
+            opt(tag("the ")),
+            ordinal,
+            space1,
+            weekday,
+            opt(preceded(
+                tuple((
+                    alt((
+                        tag(" of "),
+                        tag(" in ")
+                    )),
+                    opt(tag("the "))
+                )),
+                alt((
+                    current_period(config),
+                    month_of_year(config),
+                    calendar_year(config)
+                ))
+            ))
+        )).parse(input)?;
+
+        let (start, end) = match bounds_opt {
+            Some(bounds) => bounds,
+            // "last friday" alone is handled by the relative weekdays
+            None if nth < 0 => return Err(nom::Err::<()>::Error(())),
+            None => period_bounds(Local::now(), "month", 0, config.week_start)?
+        };
+
+        let day = weekday_string_to_int(day)
+                    .map_err(|_| nom::Err::<()>::Error(()))?;
+        let day = Weekday::try_from(day as u8)
+                    .map_err(|_| nom::Err::<()>::Error(()))?;
+
+        // A failure, rather than an error, stops other parsers
+        // from reading the ordinal as a day of the month
+        let dt = nth_weekday_between(start, end, day, nth)
+            .map_err(|_| nom::Err::Failure(()))?;
+
+        Ok((tail, dt))
+    }
+}
+
+/* Parse days given without a month, like "on the 15th" or "the 3rd" */
+pub fn ordinal_days(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, DateTime<Local>, ()> + '_ {
+    move |input: &str| {
+        let (tail, (_, the_opt, day, suffix_opt, _)) = tuple((
+            opt(tag("on ")),
+            opt(tag("the ")),
+            day1,
+            opt(alt((
+                tag("st"),
+                tag("nd"),
+                tag("rd"),
+                tag("th")
+            ))),
+            word_end
+        )).parse(input)?;
+
+        // A plain number is not enough to be considered a day
+        if the_opt.is_none() && suffix_opt.is_none() {
+            return Err(nom::Err::<()>::Error(()));
+        }
+
+        let now = Local::now().round_subsecs(0);
+        let dt = resolve_day_of_month(now, day, &config.day_of_month)?;
+
+        Ok((tail, dt))
+    }
+}
@@ -1,17 +1,30 @@
-use chrono::{DateTime, Local, Duration, Datelike, SubsecRound};
+use chrono::{DateTime, NaiveDate, Local, Duration, Datelike, SubsecRound, TimeZone, Weekday};
 use nom::{Parser, IResult};
 use nom::character::complete::{
     digit1,
     space1
 };
 use nom::combinator::{map_res, opt};
-use nom::sequence::tuple;
+use nom::sequence::{preceded, terminated, tuple};
 use nom::multi::many1;
 use nom::branch::alt;
 use nom::bytes::complete::tag;
-use crate::parsers::generic::weekday;
-
-use crate::utils::{weekday_string_to_int, weekday_to_int, month_future, year_future, month_past, year_past};
+use crate::{NextWeekday, ParseConfig};
+use crate::parsers::generic::{month_name, weekday, word_end};
+
+use crate::utils::{
+    weekday_string_to_int,
+    weekday_to_int,
+    weekday_in_week,
+    month_string_to_int,
+    month_future,
+    year_future,
+    month_past,
+    year_past,
+    period_bounds,
+    extract_datetime,
+    naive_to_local
+};
 
 pub fn relative_time_past(input: &str) -> IResult<&str, DateTime<Local>, ()> {
     let (tail, (data, _)) = tuple((
@@ -150,66 +163,316 @@ pub fn relative_date_future(input: &str) -> IResult<&str, DateTime<Local>, ()> {
     Ok((tail, dt))
 }
 
-pub fn relative_weekdays(input: &str) -> IResult<&str, DateTime<Local>, ()> {
-    let (tail, (rel, _, day)) = tuple((
-        alt((
-            tag("next"),
-            tag("last")
-        )),
-        space1,
-        weekday
-    )).parse(input)?;
+pub fn relative_weekdays(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, DateTime<Local>, ()> + '_ {
+    move |input: &str| {
+        let (tail, (rel, _, day)) = tuple((
+            alt((
+                tag("next"),
+                tag("last")
+            )),
+            space1,
+            weekday
+        )).parse(input)?;
+
+        let dt = Local::now().round_subsecs(0);
+
+        let to = weekday_string_to_int(day)
+                    .map_err(|_| nom::Err::<()>::Error(()))?;
+
+        let from = weekday_to_int(dt.weekday());
+
+        let days_diff = match (rel, &config.next_weekday) {
+            ("next", NextWeekday::Coming) => days_until(from, to, false),
+            ("next", NextWeekday::FollowingWeek) => {
+                let (next_week, _) = period_bounds(dt, "week", 1, config.week_start)?;
+                let offset = weekday_in_week(to, config.week_start);
+                (next_week.date_naive() - dt.date_naive()).num_days() + offset
+            },
+            _ => - ((from - to - 1).rem_euclid(7) + 1)
+        };
+
+        let result = dt + Duration::days(days_diff);
+
+        Ok((tail, result))
+    }
+}
+
+pub fn current_weekdays(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, DateTime<Local>, ()> + '_ {
+    move |input: &str| {
+        let (tail, (this_opt, day)) = tuple((
+            opt(tag("this ")),
+            weekday
+        )).parse(input)?;
+
+        let dt = Local::now().round_subsecs(0);
+        let cur_weekday = weekday_to_int(dt.weekday());
+
+        let to = weekday_string_to_int(day)
+                    .map_err(|_| nom::Err::<()>::Error(()))?;
 
-    let dt = Local::now().round_subsecs(0);
+        // "this saturday" is the one in the current week, while
+        // "saturday" is just the next one
+        let days_diff = match this_opt {
+            Some(_) => weekday_in_week(to, config.week_start) - weekday_in_week(cur_weekday, config.week_start),
+            None => days_until(cur_weekday, to, config.weekday_includes_today)
+        };
 
-    let to = weekday_string_to_int(day);
+        let result = dt + Duration::days(days_diff);
 
-    if to.is_err() {
-        return Err(nom::Err::<()>::Error(()));
+        Ok((tail, result))
     }
+}
 
-    let to = to.unwrap();
+/* Parse weekdays relative to a week, like "monday next week",
+ * "friday after next", "a week from tuesday" or "tuesday week" */
+pub fn week_weekdays(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, DateTime<Local>, ()> + '_ {
+    move |input: &str| {
+        let dt = Local::now().round_subsecs(0);
+        let cur_weekday = weekday_to_int(dt.weekday());
+
+        let (tail, days_diff) = alt((
+            map_res(
+                tuple((
+                    weekday,
+                    opt(tag(" of")),
+                    space1,
+                    alt((
+                        tag("this"),
+                        tag("next"),
+                        tag("last")
+                    )),
+                    tag(" week"),
+                    word_end
+                )),
+                |(day, _, _, rel, _, _)| {
+                    let to = weekday_string_to_int(day)?;
+                    let offset = match rel {
+                        "next" => 1,
+                        "last" => -1,
+                        _ => 0
+                    };
+
+                    let (week, _) = period_bounds(dt, "week", offset, config.week_start)
+                                        .map_err(|_| ())?;
+                    let from_week_start = weekday_in_week(to, config.week_start);
+
+                    Ok::<i64, ()>((week.date_naive() - dt.date_naive()).num_days() + from_week_start)
+                }
+            ),
+            map_res(
+                terminated(weekday, tag(" after next")),
+                |day| weekday_string_to_int(day)
+                        .map(|to| days_until(cur_weekday, to, false) + 7)
+            ),
+            map_res(
+                alt((
+                    preceded(tag("a week from "), weekday),
+                    terminated(weekday, tuple((tag(" week"), word_end)))
+                )),
+                |day| weekday_string_to_int(day)
+                        .map(|to| days_until(cur_weekday, to, config.weekday_includes_today) + 7)
+            )
+        )).parse(input)?;
 
-    let from = weekday_to_int(dt.weekday());
+        let result = dt + Duration::days(days_diff);
 
-    // Unwrap is safe because of previous check
-    let days_diff = match rel {
-        "next" => 7 + (to - from),
-        "last" => if to >= from {
-            - (7 + (from - to))
-            } else {
-                to - from
+        Ok((tail, result))
+    }
+}
+
+/* Parse weekends and working days, like "this weekend", "next weekend",
+ * "the next weekday" or "on weekdays", returning the first and last
+ * second of the span */
+pub fn weekends(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, (DateTime<Local>, DateTime<Local>), ()> + '_ {
+    move |input: &str| {
+        let (tail, (_, rel, _, span)) = tuple((
+            opt(alt((
+                tag("over "),
+                tag("on "),
+                tag("the ")
+            ))),
+            opt(terminated(
+                alt((
+                    tag("this"),
+                    tag("next"),
+                    tag("last")
+                )),
+                space1
+            )),
+            opt(tag("the ")),
+            terminated(
+                alt((
+                    tag("weekends"),
+                    tag("weekend"),
+                    tag("weekdays"),
+                    tag("weekday"),
+                    tag("working days"),
+                    tag("working day"),
+                    tag("business days"),
+                    tag("business day")
+                )),
+                word_end
+            )
+        )).parse(input)?;
+
+        let today = Local::now().date_naive();
+        let (weekend_start, weekend_end) = config.weekend;
+        let weekend_length = weekday_in_week(weekday_to_int(weekend_end), weekend_start) + 1;
+        let in_weekend = |day: NaiveDate| weekday_in_week(weekday_to_int(day.weekday()), weekend_start) < weekend_length;
+
+        // A weekend covering the whole week leaves no working days to find
+        let skip_weekend = |mut day: NaiveDate, step: i64| {
+            for _ in 0..7 {
+                if !in_weekend(day) {
+                    return Ok(day);
+                }
+                day += Duration::days(step);
+            }
+            Err(nom::Err::<()>::Error(()))
+        };
+
+        let (first, last) = match (span, rel) {
+            ("weekend" | "weekends", _) => {
+                // The current weekend if we're in one, otherwise the coming one
+                let into_weekend = weekday_in_week(weekday_to_int(today.weekday()), weekend_start);
+                let mut first = if into_weekend < weekend_length {
+                    today - Duration::days(into_weekend)
+                } else {
+                    today + Duration::days(7 - into_weekend)
+                };
+
+                match (rel, &config.next_weekday) {
+                    (Some("next"), NextWeekday::FollowingWeek) => first += Duration::weeks(1),
+                    (Some("next"), NextWeekday::Coming) if first <= today => first += Duration::weeks(1),
+                    (Some("last"), _) => {
+                        first -= Duration::weeks(1);
+                        if first + Duration::days(weekend_length - 1) >= today {
+                            first -= Duration::weeks(1);
+                        }
+                    },
+                    _ => ()
+                }
+
+                (first, first + Duration::days(weekend_length - 1))
             },
-        _ => -1 // this is impossible, the nom parser will error
-    };
+            // "on weekdays" is the current or coming run of working days
+            ("weekdays" | "working days" | "business days", None) => {
+                let first = skip_weekend(today, 1)?;
+                let mut last = first;
 
-    let result = dt + Duration::days(days_diff);
+                while !in_weekend(last + Duration::days(1)) {
+                    last += Duration::days(1);
+                }
 
-    Ok((tail, result))
+                (first, last)
+            },
+            // Otherwise, only "next weekday" and "last weekday" make sense
+            (_, Some("this")) | (_, None) => return Err(nom::Err::<()>::Error(())),
+            (_, Some(rel)) => {
+                let step = if rel == "next" { 1 } else { -1 };
+                let day = skip_weekend(today + Duration::days(step), step)?;
+
+                (day, day)
+            }
+        };
+
+        Ok((tail, (
+            naive_to_local(first, 0, 0, 0)?,
+            naive_to_local(last, 23, 59, 59)?
+        )))
+    }
 }
 
-pub fn current_weekdays(input: &str) -> IResult<&str, DateTime<Local>, ()> {
-    let (tail, (_, day)) = tuple((
-        opt(tag("this ")),
-        weekday
-    )).parse(input)?;
+/* Number of days until the next occurrence of a weekday */
+fn days_until(from: i64, to: i64, include_today: bool) -> i64 {
+    if include_today {
+        (to - from).rem_euclid(7)
+    } else {
+        (to - from - 1).rem_euclid(7) + 1
+    }
+}
 
-    let dt = Local::now().round_subsecs(0);
-    let cur_weekday = weekday_to_int(dt.weekday());
+pub fn relative_periods(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, (DateTime<Local>, DateTime<Local>), ()> + '_ {
+    move |input: &str| {
+        let (tail, (rel, _, period)) = tuple((
+            alt((
+                tag("this"),
+                tag("next"),
+                tag("last")
+            )),
+            space1,
+            terminated(
+                alt((
+                    tag("week"),
+                    tag("month"),
+                    tag("quarter"),
+                    tag("year")
+                )),
+                word_end
+            )
+        )).parse(input)?;
+
+        let offset = match rel {
+            "next" => 1,
+            "last" => -1,
+            _ => 0
+        };
 
-    let to = weekday_string_to_int(day);
+        let now = Local::now().round_subsecs(0);
+        let range = period_bounds(now, period, offset, config.week_start)?;
 
-    if to.is_err() {
-        return Err(nom::Err::<()>::Error(()));
+        Ok((tail, range))
     }
+}
 
-    let to = to.unwrap();
+pub fn relative_months(input: &str) -> IResult<&str, (DateTime<Local>, DateTime<Local>), ()> {
+    let (tail, (rel, _, m_str, _)) = tuple((
+        alt((
+            tag("this"),
+            tag("next"),
+            tag("last")
+        )),
+        space1,
+        month_name,
+        word_end
+    )).parse(input)?;
 
-    if cur_weekday >= to {
-        return Err(nom::Err::<()>::Error(()));
-    }
+    let month = month_string_to_int(m_str)
+                .map_err(|_| nom::Err::<()>::Error(()))?;
+
+    let now = Local::now();
+    let year = now.year();
+
+    let year = match rel {
+        "next" => if month > now.month() { year } else { year + 1 },
+        "last" => if month < now.month() { year } else { year - 1 },
+        _ => year
+    };
+
+    let first = extract_datetime(Local.with_ymd_and_hms(year, month, 1, 0, 0, 0))?;
+    // The week start doesn't matter when looking for months
+    let range = period_bounds(first, "month", 0, Weekday::Mon)?;
+
+    Ok((tail, range))
+}
 
-    let result = dt + Duration::days(to - cur_weekday);
+/* Parse a year relative to the current one, like " next year" */
+pub fn relative_year(input: &str) -> IResult<&str, i32, ()> {
+    let (tail, (_, rel, _)) = tuple((
+        space1,
+        alt((
+            tag("this"),
+            tag("next"),
+            tag("last")
+        )),
+        tag(" year")
+    )).parse(input)?;
 
-    Ok((tail, result))
+    let year = Local::now().year();
+
+    match rel {
+        "next" => Ok((tail, year + 1)),
+        "last" => Ok((tail, year - 1)),
+        _ => Ok((tail, year))
+    }
 }
@@ -0,0 +1,307 @@
+use chrono::prelude::*;
+use nom::{Parser, IResult};
+use nom::branch::alt;
+use nom::bytes::complete::{tag, take_while_m_n};
+use nom::character::complete::{alpha1, char, one_of, space1};
+use nom::combinator::{map, map_res, opt};
+use nom::sequence::{preceded, terminated, tuple};
+
+use crate::{ParseConfig, TwoDigitYears, WeekdayCheck};
+use crate::parsers::generic::*;
+use crate::utils::{expand_year, month_string_to_int, weekday_string_to_int, weekday_to_int};
+
+/* Parse the dates used by emails and HTTP headers, together with RFC 3339 */
+pub fn internet_dates(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, DateTime<Local>, ()> + '_ {
+    move |input: &str| {
+        alt((
+            parse_rfc3339,
+            parse_rfc2822(config),
+            parse_rfc850(config),
+            parse_asctime(config)
+        )).parse(input)
+    }
+}
+
+/* Parse RFC 3339 timestamps, like "2024-06-05t07:02:24.123+02:00" */
+pub fn parse_rfc3339(input: &str) -> IResult<&str, DateTime<Local>, ()> {
+    let (tail, (year, _, month, _, day, _, (hour, minute, second), ms_opt, offset)) = tuple((
+        year4,
+        tag("-"),
+        month2,
+        tag("-"),
+        day2,
+        one_of("t "),
+        clock,
+        opt(preceded(char('.'), millisecond)),
+        alt((
+            map(char('z'), |_| 0),
+            numeric_zone
+        ))
+    )).parse(input)?;
+
+    let dt = with_offset(year, month, day, (hour, minute, second), offset)?;
+    let dt = dt.with_nanosecond(ms_opt.unwrap_or(0) * 1_000_000)
+        .ok_or(nom::Err::Error(()))?;
+
+    Ok((tail, dt.with_timezone(&Local)))
+}
+
+/* Parse RFC 2822 dates, like "sat, 18 oct 2026 09:27:01 +0000".
+ * This also covers the IMF-fixdate format of HTTP */
+pub fn parse_rfc2822(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, DateTime<Local>, ()> + '_ {
+    move |input: &str| {
+        let (tail, (weekday_opt, day, _, month, _, year, _, time, _, offset)) = tuple((
+            opt(terminated(weekday, tuple((opt(tag(",")), space1)))),
+            day1,
+            space1,
+            month_abbreviation,
+            space1,
+            alt((
+                year4,
+                // Obsolete two-digit years, from 1950 to 2049
+                map(two_digits, |y| expand_year(y, &TwoDigitYears::Pivot(50), Utc::now().year()))
+            )),
+            space1,
+            clock,
+            space1,
+            zone
+        )).parse(input)?;
+
+        let dt = with_offset(year, month, day, time, offset)?;
+
+        check_weekday(config, weekday_opt, &dt)?;
+
+        Ok((tail, dt.with_timezone(&Local)))
+    }
+}
+
+/* Parse RFC 850 dates, like "sunday, 06-nov-94 08:49:37 gmt" */
+pub fn parse_rfc850(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, DateTime<Local>, ()> + '_ {
+    move |input: &str| {
+        let (tail, (weekday, _, day, _, month, _, year, _, time, _, offset)) = tuple((
+            weekday,
+            tag(", "),
+            day2,
+            tag("-"),
+            month_abbreviation,
+            tag("-"),
+            two_digits,
+            space1,
+            clock,
+            space1,
+            zone
+        )).parse(input)?;
+
+        // Years that would be more than 50 years in the future are in the past
+        let year = expand_year(year, &TwoDigitYears::Window(50), Utc::now().year());
+
+        let dt = with_offset(year, month, day, time, offset)?;
+
+        check_weekday(config, Some(weekday), &dt)?;
+
+        Ok((tail, dt.with_timezone(&Local)))
+    }
+}
+
+/* Parse dates in the format of C's asctime, like
+ * "sun nov  6 08:49:37 1994", which are always in UTC */
+pub fn parse_asctime(config: &ParseConfig) -> impl Fn(&str) -> IResult<&str, DateTime<Local>, ()> + '_ {
+    move |input: &str| {
+        let (tail, (weekday, _, month, _, day, _, time, _, year)) = tuple((
+            weekday,
+            space1,
+            month_abbreviation,
+            space1,
+            day1,
+            space1,
+            clock,
+            space1,
+            year4
+        )).parse(input)?;
+
+        let dt = with_offset(year, month, day, time, 0)?;
+
+        check_weekday(config, Some(weekday), &dt)?;
+
+        Ok((tail, dt.with_timezone(&Local)))
+    }
+}
+
+/* Parse a time with seconds, like "09:27:01", or without them in RFC 2822 */
+fn clock(input: &str) -> IResult<&str, (u32, u32, u32), ()> {
+    map(
+        tuple((
+            hour2,
+            tag(":"),
+            minute2,
+            opt(preceded(tag(":"), second2))
+        )),
+        |(hour, _, minute, sec_opt)| (hour, minute, sec_opt.unwrap_or(0))
+    ).parse(input)
+}
+
+fn month_abbreviation(input: &str) -> IResult<&str, u32, ()> {
+    map_res(
+        month_name,
+        month_string_to_int
+    ).parse(input)
+}
+
+fn two_digits(input: &str) -> IResult<&str, i32, ()> {
+    map_res(
+        take_while_m_n(2, 2, |c| char::is_digit(c, 10)),
+        |y: &str| y.parse::<i32>()
+    ).parse(input)
+}
+
+/* Parse an offset from UTC, like "+0200" or "-05:00", in seconds */
+fn numeric_zone(input: &str) -> IResult<&str, i32, ()> {
+    let (tail, (sign, hours, _, minutes)) = tuple((
+        one_of("+-"),
+        hour2,
+        opt(tag(":")),
+        minute2
+    )).parse(input)?;
+
+    let offset = (hours * 3600 + minutes * 60) as i32;
+
+    match sign {
+        '-' => Ok((tail, -offset)),
+        _ => Ok((tail, offset))
+    }
+}
+
+/* Parse an offset written as a number or with one of the
+ * obsolete zone names of RFC 2822, like "gmt" or "est" */
+fn zone(input: &str) -> IResult<&str, i32, ()> {
+    alt((
+        numeric_zone,
+        map_res(
+            terminated(alpha1, word_end),
+            |name: &str| {
+                let hours = match name {
+                    "ut" | "utc" | "gmt" | "z" => 0,
+                    "edt" => -4,
+                    "est" | "cdt" => -5,
+                    "cst" | "mdt" => -6,
+                    "mst" | "pdt" => -7,
+                    "pst" => -8,
+                    // Military zones were often used wrongly, so RFC 2822
+                    // suggests ignoring them, which here means using UTC
+                    military if military.len() == 1 && military != "j" => 0,
+                    _ => return Err(())
+                };
+
+                Ok(hours * 3600)
+            }
+        )
+    )).parse(input)
+}
+
+/* Compare the weekday written in the date with the real one,
+ * in the date's own time zone, if the configuration asks for it */
+fn check_weekday(
+    config: &ParseConfig,
+    weekday_opt: Option<&str>,
+    dt: &DateTime<FixedOffset>
+) -> Result<(), nom::Err<()>> {
+    let Some(weekday) = weekday_opt else {
+        return Ok(());
+    };
+
+    let weekday = weekday_string_to_int(weekday)
+        .map_err(|_| nom::Err::<()>::Error(()))?;
+
+    // A failure, rather than an error, stops other parsers
+    // from reading the date without its weekday
+    if config.weekday_check == WeekdayCheck::Fail && weekday != weekday_to_int(dt.weekday()) {
+        return Err(nom::Err::Failure(()));
+    }
+
+    Ok(())
+}
+
+fn with_offset(
+    year: i32,
+    month: u32,
+    day: u32,
+    (hour, minute, second): (u32, u32, u32),
+    offset: i32
+) -> Result<DateTime<FixedOffset>, nom::Err<()>> {
+    let zone = FixedOffset::east_opt(offset).ok_or(nom::Err::Error(()))?;
+
+    zone.with_ymd_and_hms(year, month, day, hour, minute, second)
+        .single()
+        .ok_or(nom::Err::Error(()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(dt: DateTime<Local>) -> String {
+        dt.with_timezone(&Utc).format("%Y-%m-%d %H:%M:%S").to_string()
+    }
+
+    #[test]
+    fn parse_rfc2822_test() {
+        let config = ParseConfig::default();
+
+        let (_, dt) = parse_rfc2822(&config)("sat, 18 oct 2026 09:27:01 +0000").unwrap();
+        assert_eq!(utc(dt), "2026-10-18 09:27:01");
+
+        let (_, dt) = parse_rfc2822(&config)("18 oct 2026 09:27 -0230").unwrap();
+        assert_eq!(utc(dt), "2026-10-18 11:57:00");
+
+        let (_, dt) = parse_rfc2822(&config)("fri, 17 oct 97 09:27:01 est").unwrap();
+        assert_eq!(utc(dt), "1997-10-17 14:27:01");
+
+        let (_, dt) = parse_rfc2822(&config)("sun, 06 nov 1994 08:49:37 gmt").unwrap();
+        assert_eq!(utc(dt), "1994-11-06 08:49:37");
+
+        assert!(parse_rfc2822(&config)("18 oct 2026 09:27:01 abc").is_err());
+    }
+
+    #[test]
+    fn parse_rfc3339_test() {
+        let (_, dt) = parse_rfc3339("2024-06-05t07:02:24z").unwrap();
+        assert_eq!(utc(dt), "2024-06-05 07:02:24");
+
+        let (_, dt) = parse_rfc3339("2024-06-05 07:02:24.250+02:00").unwrap();
+        assert_eq!(utc(dt), "2024-06-05 05:02:24");
+        assert!(dt.timestamp_subsec_millis() == 250);
+
+        // The offset is required
+        assert!(parse_rfc3339("2024-06-05t07:02:24").is_err());
+    }
+
+    #[test]
+    fn parse_http_dates_test() {
+        let config = ParseConfig::default();
+
+        let (_, dt) = parse_rfc850(&config)("sunday, 06-nov-94 08:49:37 gmt").unwrap();
+        assert_eq!(utc(dt), "1994-11-06 08:49:37");
+
+        let (_, dt) = parse_asctime(&config)("sun nov  6 08:49:37 1994").unwrap();
+        assert_eq!(utc(dt), "1994-11-06 08:49:37");
+    }
+
+    #[test]
+    fn weekday_check_test() {
+        let config = ParseConfig {
+            weekday_check: WeekdayCheck::Fail,
+            ..Default::default()
+        };
+
+        assert!(parse_rfc2822(&config)("sun, 06 nov 1994 08:49:37 gmt").is_ok());
+        assert!(parse_rfc2822(&config)("mon, 06 nov 1994 08:49:37 gmt").is_err());
+        assert!(parse_rfc850(&config)("monday, 06-nov-94 08:49:37 gmt").is_err());
+        assert!(parse_asctime(&config)("mon nov  6 08:49:37 1994").is_err());
+
+        // The weekday is the one of the date in its own time zone
+        assert!(parse_rfc2822(&config)("mon, 07 nov 1994 01:00:00 +0200").is_ok());
+
+        let config = ParseConfig::default();
+        assert!(parse_rfc2822(&config)("mon, 06 nov 1994 08:49:37 gmt").is_ok());
+    }
+}
@@ -1,13 +1,17 @@
 use chrono::{
     DateTime,
+    NaiveDate,
     LocalResult,
+    Utc,
     Local,
     TimeZone,
     Datelike,
     Timelike,
     Weekday,
-    Duration
+    Duration,
+    Months
 };
+use crate::{Occurrence, TwoDigitYears, WeekNumbering};
 
 pub fn extract_datetime(res: LocalResult<DateTime<Local>>) -> Result<DateTime<Local>, nom::Err<()>> {
     match res {
@@ -17,17 +21,25 @@ pub fn extract_datetime(res: LocalResult<DateTime<Local>>) -> Result<DateTime<Lo
     }
 }
 
-pub fn join_date_time(date: DateTime<Local>, time: DateTime<Local>) -> Result<DateTime<Local>, nom::Err<()>> {
-    let dt_opt = Local.with_ymd_and_hms(
-        date.year(),
-        date.month(),
-        date.day(), 
-        time.hour(), 
-        time.minute(), 
-        time.second()
-    );
+pub fn extract_utc(res: LocalResult<DateTime<Utc>>) -> Result<DateTime<Utc>, nom::Err<()>> {
+    match res {
+        LocalResult::Single(dt) => Ok(dt),
+        _ => Err(nom::Err::<()>::Error(()))
+    }
+}
+
+/* Put the time of `time` on the date of `date`. A time that falls after
+ * `day`, the day it was given for, like "24:00", moves the date as much */
+pub fn join_date_time(
+    date: DateTime<Local>,
+    time: DateTime<Local>,
+    day: NaiveDate
+) -> Result<DateTime<Local>, nom::Err<()>> {
+    let date = date.date_naive() + (time.date_naive() - day);
 
-    extract_datetime(dt_opt)
+    let dt = naive_to_local(date, time.hour(), time.minute(), time.second())?;
+
+    dt.with_nanosecond(time.nanosecond()).ok_or(nom::Err::Error(()))
 }
 
 pub fn weekday_to_int(day: Weekday) -> i64 {
@@ -42,6 +54,12 @@ pub fn weekday_to_int(day: Weekday) -> i64 {
     }
 }
 
+/* Position of a weekday (as returned by weekday_to_int)
+ * in a week starting on `week_start` */
+pub fn weekday_in_week(day: i64, week_start: Weekday) -> i64 {
+    (day - weekday_to_int(week_start)).rem_euclid(7)
+}
+
 pub fn weekday_string_to_int(day: &str) -> Result<i64, ()> {
     match day {
         "monday"    => Ok(0),
@@ -127,14 +145,14 @@ pub fn month_future(date: DateTime<Local>, amount: u32) -> Result<DateTime<Local
         day = eonm_day;
     }
 
-    Ok(extract_datetime(Local.with_ymd_and_hms(
+    extract_datetime(Local.with_ymd_and_hms(
         year, 
         month, 
         day, 
         date.hour(), 
         date.minute(), 
         date.second()
-    ))?)
+    ))
 }
 
 pub fn year_future(date: DateTime<Local>, amount: i32) -> Result<DateTime<Local>, nom::Err<()>> {
@@ -156,14 +174,14 @@ pub fn year_future(date: DateTime<Local>, amount: i32) -> Result<DateTime<Local>
         day = eom.day();
     }
 
-    Ok(extract_datetime(Local.with_ymd_and_hms(
-        year + amount as i32,
+    extract_datetime(Local.with_ymd_and_hms(
+        year + amount,
         date.month(),
         day,
         date.hour(),
         date.minute(),
         date.second()
-    ))?)
+    ))
 }
 
 pub fn month_past(date: DateTime<Local>, amount: i32) -> Result<DateTime<Local>, nom::Err<()>> {
@@ -186,14 +204,14 @@ pub fn month_past(date: DateTime<Local>, amount: i32) -> Result<DateTime<Local>,
         day = eolm_day;
     }
 
-    Ok(extract_datetime(Local.with_ymd_and_hms(
+    extract_datetime(Local.with_ymd_and_hms(
         year, 
         month as u32,
         day, 
         date.hour(), 
         date.minute(), 
         date.second()
-    ))?)
+    ))
 }
 
 pub fn year_past(date: DateTime<Local>, amount: i32) -> Result<DateTime<Local>, nom::Err<()>> {
@@ -215,12 +233,558 @@ pub fn year_past(date: DateTime<Local>, amount: i32) -> Result<DateTime<Local>,
         day = eom.day();
     }
 
-    Ok(extract_datetime(Local.with_ymd_and_hms(
+    extract_datetime(Local.with_ymd_and_hms(
         year - amount,
         date.month(),
         day,
         date.hour(),
         date.minute(),
         date.second()
-    ))?)
+    ))
+}
+
+pub fn naive_to_local(date: NaiveDate, hour: u32, minute: u32, second: u32) -> Result<DateTime<Local>, nom::Err<()>> {
+    extract_datetime(Local.with_ymd_and_hms(
+        date.year(),
+        date.month(),
+        date.day(),
+        hour,
+        minute,
+        second
+    ))
+}
+
+/* Today's date at the given time, where 24:00:00 is the end of the
+ * day, that is midnight at the start of the next one */
+pub fn today_at(hour: u32, minute: u32, second: u32) -> Result<DateTime<Local>, nom::Err<()>> {
+    let today = Local::now().date_naive();
+
+    if (hour, minute, second) == (24, 0, 0) {
+        return naive_to_local(today + Duration::days(1), 0, 0, 0);
+    }
+
+    naive_to_local(today, hour, minute, second)
+}
+
+/* The current date, where the hours before `rollover_hour`
+ * still belong to the previous day */
+pub fn logical_today(rollover_hour: u32) -> Result<NaiveDate, nom::Err<()>> {
+    if rollover_hour >= 24 {
+        return Err(nom::Err::Error(()));
+    }
+
+    Ok((Local::now() - Duration::hours(rollover_hour as i64)).date_naive())
+}
+
+pub fn month_shift(date: DateTime<Local>, amount: i64) -> Result<DateTime<Local>, nom::Err<()>> {
+    let months = u32::try_from(amount.unsigned_abs())
+        .map(Months::new)
+        .map_err(|_| nom::Err::Error(()))?;
+
+    // Days past the end of the target month become its last day
+    let shifted = if amount >= 0 {
+        date.date_naive().checked_add_months(months)
+    } else {
+        date.date_naive().checked_sub_months(months)
+    };
+
+    naive_to_local(shifted.ok_or(nom::Err::Error(()))?, date.hour(), date.minute(), date.second())
+}
+
+/* Returns the first and last second of the period ("day", "week", "month",
+ * "quarter" or "year") containing the date, moved by `offset` periods */
+pub fn period_bounds(
+    date: DateTime<Local>,
+    period: &str,
+    offset: i32,
+    week_start: Weekday
+) -> Result<(DateTime<Local>, DateTime<Local>), nom::Err<()>> {
+    let day = date.date_naive();
+    let offset = offset as i64;
+
+    let (first, last) = match period {
+        "day" => {
+            let first = day + Duration::days(offset);
+            (first, first)
+        },
+        "week" => {
+            let back = weekday_in_week(weekday_to_int(day.weekday()), week_start);
+            let first = day - Duration::days(back) + Duration::weeks(offset);
+            (first, first + Duration::days(6))
+        },
+        "month" => {
+            let first = extract_datetime(Local.with_ymd_and_hms(day.year(), day.month(), 1, 0, 0, 0))?;
+            let first = month_shift(first, offset)?;
+            (first.date_naive(), end_of_month(first)?.date_naive())
+        },
+        "quarter" => {
+            let quarter_month = (day.month() - 1) / 3 * 3 + 1;
+            let first = extract_datetime(Local.with_ymd_and_hms(day.year(), quarter_month, 1, 0, 0, 0))?;
+            let first = month_shift(first, 3 * offset)?;
+            let last = end_of_month(month_future(first, 2)?)?;
+            (first.date_naive(), last.date_naive())
+        },
+        "year" => {
+            let year = day.year() + offset as i32;
+            let first = NaiveDate::from_ymd_opt(year, 1, 1).ok_or(nom::Err::Error(()))?;
+            let last = NaiveDate::from_ymd_opt(year, 12, 31).ok_or(nom::Err::Error(()))?;
+            (first, last)
+        },
+        _ => return Err(nom::Err::Error(()))
+    };
+
+    Ok((
+        naive_to_local(first, 0, 0, 0)?,
+        naive_to_local(last, 23, 59, 59)?
+    ))
+}
+
+/* Returns the nth occurrence of a weekday between two dates (inclusive).
+ * A negative nth counts backwards from the end date */
+pub fn nth_weekday_between(
+    start: DateTime<Local>,
+    end: DateTime<Local>,
+    day: Weekday,
+    nth: i32
+) -> Result<DateTime<Local>, nom::Err<()>> {
+    let to = weekday_to_int(day);
+    // Counting in days rather than hours keeps clear of DST changes
+    let (start, end) = (start.date_naive(), end.date_naive());
+
+    let date = if nth > 0 {
+        let first = start + Duration::days((to - weekday_to_int(start.weekday())).rem_euclid(7));
+        first + Duration::weeks(nth as i64 - 1)
+    } else {
+        let last = end - Duration::days((weekday_to_int(end.weekday()) - to).rem_euclid(7));
+        last - Duration::weeks(-nth as i64 - 1)
+    };
+
+    if date < start || date > end {
+        return Err(nom::Err::Error(()));
+    }
+
+    naive_to_local(date, 0, 0, 0)
+}
+
+fn pick_occurrence<T>(
+    reference: T,
+    current: Option<T>,
+    next: Option<T>,
+    previous: Option<T>,
+    policy: &Occurrence
+) -> Option<T>
+where
+    T: Copy + std::ops::Sub<Output = Duration>
+{
+    match policy {
+        Occurrence::Current => current,
+        Occurrence::Next => next,
+        Occurrence::Previous => previous,
+        Occurrence::Nearest => match (next, previous) {
+            (Some(n), Some(p)) if reference - p < n - reference => Some(p),
+            (Some(n), _) => Some(n),
+            (None, p) => p
+        }
+    }
+}
+
+/* Pick the month for a day given without one, like "the 15th",
+ * skipping the months that don't have that day */
+pub fn resolve_day_of_month(
+    date: DateTime<Local>,
+    day: u32,
+    policy: &Occurrence
+) -> Result<DateTime<Local>, nom::Err<()>> {
+    let today = date.date_naive();
+    let first = today.with_day(1).ok_or(nom::Err::Error(()))?;
+
+    let candidate = |months: i32| {
+        let shift = Months::new(months.unsigned_abs());
+        let month = if months < 0 {
+            first.checked_sub_months(shift)
+        } else {
+            first.checked_add_months(shift)
+        };
+
+        month.and_then(|m| m.with_day(day))
+    };
+
+    let next = (0..=12).filter_map(candidate).find(|d| *d >= today);
+    let previous = (0..=12).filter_map(|m| candidate(-m)).find(|d| *d <= today);
+
+    let result = pick_occurrence(today, candidate(0), next, previous, policy);
+
+    naive_to_local(result.ok_or(nom::Err::Error(()))?, 0, 0, 0)
+}
+
+/* Pick the year for a date given without one, like "12/06" */
+pub fn resolve_year(
+    date: DateTime<Local>,
+    month: u32,
+    day: u32,
+    policy: &Occurrence
+) -> Result<DateTime<Local>, nom::Err<()>> {
+    let today = date.date_naive();
+    let year = today.year();
+
+    // Looking a few years around is needed for the 29th of february
+    let candidate = |offset: i32| NaiveDate::from_ymd_opt(year + offset, month, day);
+
+    let next = (0..=8).filter_map(candidate).find(|d| *d >= today);
+    let previous = (0..=8).filter_map(|y| candidate(-y)).find(|d| *d <= today);
+
+    let result = pick_occurrence(today, candidate(0), next, previous, policy);
+
+    naive_to_local(result.ok_or(nom::Err::Error(()))?, 0, 0, 0)
+}
+
+/* The first date from `date` on that falls on `weekday`, moving
+ * `months` at a time and keeping the day of the month */
+pub fn next_day_on_weekday(
+    date: NaiveDate,
+    weekday: i64,
+    months: u32
+) -> Result<DateTime<Local>, nom::Err<()>> {
+    let first = date.with_day(1).ok_or(nom::Err::Error(()))?;
+
+    let candidate = |step: u32| first
+        .checked_add_months(Months::new(step * months))
+        .and_then(|m| m.with_day(date.day()));
+
+    // Any day of the month, even the 29th of february,
+    // falls on every weekday within a few hundred steps
+    let result = (0..=400)
+        .filter_map(candidate)
+        .find(|d| weekday_to_int(d.weekday()) == weekday);
+
+    naive_to_local(result.ok_or(nom::Err::Error(()))?, 0, 0, 0)
+}
+
+/* Pick the year for a month given without one, like "in march".
+ * The current month counts both as the next and the previous one */
+pub fn resolve_month(
+    date: DateTime<Local>,
+    month: u32,
+    policy: &Occurrence
+) -> Result<DateTime<Local>, nom::Err<()>> {
+    let current = date.month();
+    let year = date.year();
+
+    let next_year = if month >= current { year } else { year + 1 };
+    let previous_year = if month <= current { year } else { year - 1 };
+
+    let year = match policy {
+        Occurrence::Current => year,
+        Occurrence::Next => next_year,
+        Occurrence::Previous => previous_year,
+        Occurrence::Nearest => {
+            let months_ahead = (month + 12 - current) % 12;
+            let months_behind = (current + 12 - month) % 12;
+
+            if months_ahead <= months_behind { next_year } else { previous_year }
+        }
+    };
+
+    extract_datetime(Local.with_ymd_and_hms(year, month, 1, 0, 0, 0))
+}
+
+/* Returns the first day of a numbered week. ISO weeks always start on
+ * monday and the first one contains the 4th of january, while US weeks
+ * start on `week_start` and the first one contains the 1st of january */
+pub fn week_number_start(
+    year: i32,
+    week: u32,
+    numbering: &WeekNumbering,
+    week_start: Weekday
+) -> Result<DateTime<Local>, nom::Err<()>> {
+    if !(1..=53).contains(&week) {
+        return Err(nom::Err::Error(()));
+    }
+
+    let first = match numbering {
+        WeekNumbering::Iso => NaiveDate::from_isoywd_opt(year, week, Weekday::Mon),
+        WeekNumbering::Us => {
+            let jan_first = NaiveDate::from_ymd_opt(year, 1, 1).ok_or(nom::Err::Error(()))?;
+            let back = weekday_in_week(weekday_to_int(jan_first.weekday()), week_start);
+            let first = jan_first - Duration::days(back) + Duration::weeks(week as i64 - 1);
+
+            if first.year() > year {
+                None
+            } else {
+                Some(first)
+            }
+        }
+    };
+
+    naive_to_local(first.ok_or(nom::Err::Error(()))?, 0, 0, 0)
+}
+
+/* Choose between the am and pm reading of an hour (1 to 12). Spans of
+ * the day that end by noon take am, and those that start from noon take
+ * pm. Spans that cross noon, or run into the night, take the reading
+ * inside or closest to them */
+pub fn hour_in_span(hour: u32, start: u32, end: u32) -> u32 {
+    let am = hour % 12;
+    let pm = am + 12;
+
+    if end <= 12 {
+        return am;
+    }
+
+    if start >= 12 && end < 23 {
+        return pm;
+    }
+
+    let distance = |h: u32| {
+        if (start..=end).contains(&h) {
+            return 0;
+        }
+
+        let to_start = (start + 24 - h) % 24;
+        let from_end = (h + 24 - end) % 24;
+
+        to_start.min(from_end)
+    };
+
+    if distance(pm) < distance(am) { pm } else { am }
+}
+
+/* Pick the day for a time given without one, like "9am", moving
+ * it from the day it was parsed on if the policy asks for it */
+pub fn resolve_time(
+    now: DateTime<Local>,
+    time: DateTime<Local>,
+    policy: &Occurrence
+) -> Result<DateTime<Local>, nom::Err<()>> {
+    let at_day = |days: i64| naive_to_local(
+        time.date_naive() + Duration::days(days),
+        time.hour(),
+        time.minute(),
+        time.second()
+    ).ok().and_then(|t| t.with_nanosecond(time.nanosecond()));
+
+    let today = at_day(0);
+    let next = today.filter(|t| *t >= now).or_else(|| at_day(1));
+    let previous = today.filter(|t| *t <= now).or_else(|| at_day(-1));
+
+    pick_occurrence(now, today, next, previous, policy).ok_or(nom::Err::Error(()))
+}
+
+/* Complete a two-digit year, like the 24 in "12/06/24" */
+pub fn expand_year(year: i32, policy: &TwoDigitYears, current_year: i32) -> i32 {
+    match policy {
+        TwoDigitYears::Pivot(pivot) if year < *pivot as i32 => 2000 + year,
+        TwoDigitYears::Pivot(_) => 1900 + year,
+        TwoDigitYears::Window(ahead) => {
+            // The latest year ending with those two digits, up to `ahead` years from now
+            let latest = current_year + *ahead as i32;
+            latest - (latest - year).rem_euclid(100)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn period_bounds_test() {
+        // Wednesday
+        let date = Local.with_ymd_and_hms(2024, 5, 15, 10, 30, 0).unwrap();
+
+        let (start, end) = period_bounds(date, "week", 1, Weekday::Mon).unwrap();
+        assert_eq!(start, Local.with_ymd_and_hms(2024, 5, 20, 0, 0, 0).unwrap());
+        assert_eq!(end, Local.with_ymd_and_hms(2024, 5, 26, 23, 59, 59).unwrap());
+
+        let (start, _) = period_bounds(date, "week", 0, Weekday::Sun).unwrap();
+        assert_eq!(start, Local.with_ymd_and_hms(2024, 5, 12, 0, 0, 0).unwrap());
+
+        let (start, end) = period_bounds(date, "month", -1, Weekday::Mon).unwrap();
+        assert_eq!(start, Local.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap());
+        assert_eq!(end, Local.with_ymd_and_hms(2024, 4, 30, 23, 59, 59).unwrap());
+
+        let (start, end) = period_bounds(date, "quarter", 1, Weekday::Mon).unwrap();
+        assert_eq!(start, Local.with_ymd_and_hms(2024, 7, 1, 0, 0, 0).unwrap());
+        assert_eq!(end, Local.with_ymd_and_hms(2024, 9, 30, 23, 59, 59).unwrap());
+
+        let (start, end) = period_bounds(date, "year", 0, Weekday::Mon).unwrap();
+        assert_eq!(start, Local.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+        assert_eq!(end, Local.with_ymd_and_hms(2024, 12, 31, 23, 59, 59).unwrap());
+    }
+
+    #[test]
+    fn nth_weekday_between_test() {
+        let (start, end) = period_bounds(
+            Local.with_ymd_and_hms(2024, 3, 10, 0, 0, 0).unwrap(),
+            "month",
+            0,
+            Weekday::Mon
+        ).unwrap();
+
+        let dt = nth_weekday_between(start, end, Weekday::Tue, 2).unwrap();
+        assert_eq!(dt, Local.with_ymd_and_hms(2024, 3, 12, 0, 0, 0).unwrap());
+
+        let dt = nth_weekday_between(start, end, Weekday::Sun, -1).unwrap();
+        assert_eq!(dt, Local.with_ymd_and_hms(2024, 3, 31, 0, 0, 0).unwrap());
+
+        assert!(nth_weekday_between(start, end, Weekday::Mon, 5).is_err());
+    }
+
+    #[test]
+    fn resolve_day_of_month_test() {
+        let date = Local.with_ymd_and_hms(2024, 1, 30, 12, 0, 0).unwrap();
+
+        let dt = resolve_day_of_month(date, 15, &Occurrence::Next).unwrap();
+        assert_eq!(dt, Local.with_ymd_and_hms(2024, 2, 15, 0, 0, 0).unwrap());
+
+        let dt = resolve_day_of_month(date, 15, &Occurrence::Previous).unwrap();
+        assert_eq!(dt, Local.with_ymd_and_hms(2024, 1, 15, 0, 0, 0).unwrap());
+
+        let dt = resolve_day_of_month(date, 28, &Occurrence::Nearest).unwrap();
+        assert_eq!(dt, Local.with_ymd_and_hms(2024, 1, 28, 0, 0, 0).unwrap());
+
+        // February 2024 doesn't have a 31st
+        let dt = resolve_day_of_month(date, 31, &Occurrence::Next).unwrap();
+        assert_eq!(dt, Local.with_ymd_and_hms(2024, 1, 31, 0, 0, 0).unwrap());
+        let dt = resolve_day_of_month(dt + Duration::days(1), 31, &Occurrence::Next).unwrap();
+        assert_eq!(dt, Local.with_ymd_and_hms(2024, 3, 31, 0, 0, 0).unwrap());
+
+        let dt = resolve_day_of_month(date, 30, &Occurrence::Current).unwrap();
+        assert_eq!(dt, Local.with_ymd_and_hms(2024, 1, 30, 0, 0, 0).unwrap());
+        assert!(resolve_day_of_month(date, 32, &Occurrence::Next).is_err());
+    }
+
+    #[test]
+    fn resolve_year_test() {
+        let date = Local.with_ymd_and_hms(2024, 11, 20, 12, 0, 0).unwrap();
+
+        let dt = resolve_year(date, 1, 5, &Occurrence::Current).unwrap();
+        assert_eq!(dt, Local.with_ymd_and_hms(2024, 1, 5, 0, 0, 0).unwrap());
+
+        let dt = resolve_year(date, 1, 5, &Occurrence::Next).unwrap();
+        assert_eq!(dt, Local.with_ymd_and_hms(2025, 1, 5, 0, 0, 0).unwrap());
+
+        let dt = resolve_year(date, 12, 5, &Occurrence::Previous).unwrap();
+        assert_eq!(dt, Local.with_ymd_and_hms(2023, 12, 5, 0, 0, 0).unwrap());
+
+        let dt = resolve_year(date, 1, 5, &Occurrence::Nearest).unwrap();
+        assert_eq!(dt, Local.with_ymd_and_hms(2025, 1, 5, 0, 0, 0).unwrap());
+
+        let dt = resolve_year(date, 2, 29, &Occurrence::Next).unwrap();
+        assert_eq!(dt, Local.with_ymd_and_hms(2028, 2, 29, 0, 0, 0).unwrap());
+        assert!(resolve_year(date, 2, 30, &Occurrence::Next).is_err());
+    }
+
+    #[test]
+    fn next_day_on_weekday_test() {
+        // Sunday
+        let date = NaiveDate::from_ymd_opt(2026, 11, 15).unwrap();
+
+        let dt = next_day_on_weekday(date, 6, 1).unwrap();
+        assert_eq!(dt, Local.with_ymd_and_hms(2026, 11, 15, 0, 0, 0).unwrap());
+
+        let dt = next_day_on_weekday(date, 0, 1).unwrap();
+        assert_eq!(dt, Local.with_ymd_and_hms(2027, 2, 15, 0, 0, 0).unwrap());
+
+        let dt = next_day_on_weekday(date, 0, 12).unwrap();
+        assert_eq!(dt, Local.with_ymd_and_hms(2027, 11, 15, 0, 0, 0).unwrap());
+
+        let date = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+        let dt = next_day_on_weekday(date, 0, 12).unwrap();
+        assert_eq!(dt, Local.with_ymd_and_hms(2044, 2, 29, 0, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn resolve_month_test() {
+        let date = Local.with_ymd_and_hms(2024, 11, 20, 12, 0, 0).unwrap();
+
+        let dt = resolve_month(date, 11, &Occurrence::Next).unwrap();
+        assert_eq!(dt, Local.with_ymd_and_hms(2024, 11, 1, 0, 0, 0).unwrap());
+
+        let dt = resolve_month(date, 3, &Occurrence::Next).unwrap();
+        assert_eq!(dt, Local.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap());
+
+        let dt = resolve_month(date, 12, &Occurrence::Previous).unwrap();
+        assert_eq!(dt, Local.with_ymd_and_hms(2023, 12, 1, 0, 0, 0).unwrap());
+
+        let dt = resolve_month(date, 1, &Occurrence::Nearest).unwrap();
+        assert_eq!(dt, Local.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap());
+
+        let dt = resolve_month(date, 8, &Occurrence::Nearest).unwrap();
+        assert_eq!(dt, Local.with_ymd_and_hms(2024, 8, 1, 0, 0, 0).unwrap());
+
+        let date = Local.with_ymd_and_hms(2024, 2, 10, 12, 0, 0).unwrap();
+        let dt = resolve_month(date, 12, &Occurrence::Nearest).unwrap();
+        assert_eq!(dt, Local.with_ymd_and_hms(2023, 12, 1, 0, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn week_number_start_test() {
+        let dt = week_number_start(2024, 1, &WeekNumbering::Iso, Weekday::Sun).unwrap();
+        assert_eq!(dt, Local.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+
+        let dt = week_number_start(2021, 1, &WeekNumbering::Iso, Weekday::Mon).unwrap();
+        assert_eq!(dt, Local.with_ymd_and_hms(2021, 1, 4, 0, 0, 0).unwrap());
+
+        let dt = week_number_start(2021, 1, &WeekNumbering::Us, Weekday::Sun).unwrap();
+        assert_eq!(dt, Local.with_ymd_and_hms(2020, 12, 27, 0, 0, 0).unwrap());
+
+        let dt = week_number_start(2021, 2, &WeekNumbering::Us, Weekday::Sun).unwrap();
+        assert_eq!(dt, Local.with_ymd_and_hms(2021, 1, 3, 0, 0, 0).unwrap());
+
+        assert!(week_number_start(2021, 54, &WeekNumbering::Us, Weekday::Sun).is_err());
+        assert!(week_number_start(2021, 53, &WeekNumbering::Iso, Weekday::Mon).is_err());
+        assert!(week_number_start(2021, 0, &WeekNumbering::Us, Weekday::Sun).is_err());
+        assert!(week_number_start(2021, 4000000000, &WeekNumbering::Us, Weekday::Sun).is_err());
+    }
+
+    #[test]
+    fn hour_in_span_test() {
+        // evening
+        assert_eq!(hour_in_span(5, 18, 21), 17);
+        // morning
+        assert_eq!(hour_in_span(7, 8, 12), 7);
+        assert_eq!(hour_in_span(1, 8, 12), 1);
+        assert_eq!(hour_in_span(2, 8, 12), 2);
+        assert_eq!(hour_in_span(3, 8, 12), 3);
+        // night
+        assert_eq!(hour_in_span(3, 20, 23), 3);
+        assert_eq!(hour_in_span(11, 20, 23), 23);
+        assert_eq!(hour_in_span(12, 20, 23), 0);
+        // lunchtime
+        assert_eq!(hour_in_span(1, 12, 14), 13);
+    }
+
+    #[test]
+    fn resolve_time_test() {
+        let now = Local.with_ymd_and_hms(2024, 5, 15, 15, 0, 0).unwrap();
+        let nine = Local.with_ymd_and_hms(2024, 5, 15, 9, 0, 0).unwrap();
+
+        let dt = resolve_time(now, nine, &Occurrence::Current).unwrap();
+        assert_eq!(dt, nine);
+
+        let dt = resolve_time(now, nine, &Occurrence::Next).unwrap();
+        assert_eq!(dt, Local.with_ymd_and_hms(2024, 5, 16, 9, 0, 0).unwrap());
+
+        let dt = resolve_time(now, nine, &Occurrence::Previous).unwrap();
+        assert_eq!(dt, nine);
+
+        let dt = resolve_time(now, nine, &Occurrence::Nearest).unwrap();
+        assert_eq!(dt, nine);
+
+        let one_am = Local.with_ymd_and_hms(2024, 5, 15, 1, 0, 0).unwrap();
+        let dt = resolve_time(now, one_am, &Occurrence::Nearest).unwrap();
+        assert_eq!(dt, Local.with_ymd_and_hms(2024, 5, 16, 1, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn expand_year_test() {
+        assert_eq!(expand_year(24, &TwoDigitYears::Pivot(50), 2026), 2024);
+        assert_eq!(expand_year(50, &TwoDigitYears::Pivot(50), 2026), 1950);
+
+        assert_eq!(expand_year(24, &TwoDigitYears::Window(20), 2026), 2024);
+        assert_eq!(expand_year(46, &TwoDigitYears::Window(20), 2026), 2046);
+        assert_eq!(expand_year(47, &TwoDigitYears::Window(20), 2026), 1947);
+        assert_eq!(expand_year(5, &TwoDigitYears::Window(20), 2090), 2105);
+    }
 }